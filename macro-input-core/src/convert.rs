@@ -1,12 +1,70 @@
-use syn::{Error, Lit, Meta, Result};
+use crate::{Meta, MetaList, MetaNameValue, NestedMeta, Value};
+use proc_macro2::Span;
+use quote::ToTokens;
+use std::{fmt::Display, str::FromStr};
+use syn::{
+    parse::Parser, parse2, parse_quote, punctuated::Punctuated, spanned::Spanned, token::Comma,
+    Error, Expr, Ident, Lit, LitBool, LitByteStr, LitChar, LitFloat, LitInt, LitStr, Path, Result,
+    Token, WherePredicate,
+};
 
-/// a trait for extracting a value from `Option<syn::Meta>`
+/// a trait for extracting a value from `Option<Meta>`
 pub trait FromMeta: Sized {
     /// extract the value
     ///
     /// # Errors
     /// may return an Error if the meta doesn't contain the correct value
     fn from(meta: Option<Meta>) -> Result<Self>;
+
+    /// extract all values from a list eg `bar("a", "b")` in `#[foo(bar("a", "b"))]`
+    ///
+    /// By default every element of the list is a separate value.
+    /// Types that are parsed from a list themselves should override this to parse the whole list as one value.
+    ///
+    /// # Errors
+    /// may return an Error if an element doesn't contain the correct value
+    fn from_list(list: MetaList) -> Result<Vec<Self>> {
+        let path = list.path;
+        list.nested
+            .into_iter()
+            .map(|nested| {
+                let meta = match nested {
+                    NestedMeta::Meta(meta) => meta,
+                    NestedMeta::Value(value) => Meta::NameValue(MetaNameValue {
+                        path: path.clone(),
+                        eq_token: Token![=](value.span()),
+                        value,
+                    }),
+                };
+                Self::from(Some(meta))
+            })
+            .collect()
+    }
+
+    /// extract all values from a list together with the span each value was parsed from
+    ///
+    /// By default every value gets the span of the whole list.
+    /// Types that parse every element as a separate value should override this to return the spans of the elements.
+    ///
+    /// # Errors
+    /// may return an Error if an element doesn't contain the correct value
+    fn from_list_spanned(list: MetaList) -> Result<Vec<(Self, Span)>> {
+        let span = list.span();
+        Self::from_list(list).map(|values| values.into_iter().map(|value| (value, span)).collect())
+    }
+}
+
+/// pair the values of a list with the spans of its elements, `from_list` has to return one value per element
+fn element_spans<T>(
+    list: MetaList,
+    from_list: impl FnOnce(MetaList) -> Result<Vec<T>>,
+) -> Result<Vec<(T, Span)>> {
+    let spans = list
+        .nested
+        .iter()
+        .map(|nested| nested.span())
+        .collect::<Vec<_>>();
+    from_list(list).map(|values| values.into_iter().zip(spans).collect())
 }
 
 impl FromMeta for Option<()> {
@@ -19,6 +77,23 @@ impl FromMeta for Option<()> {
             }
         })
     }
+
+    fn from_list_spanned(list: MetaList) -> Result<Vec<(Self, Span)>> {
+        element_spans(list, Self::from_list)
+    }
+}
+
+impl FromMeta for MetaList {
+    fn from(meta: Option<Meta>) -> Result<Self> {
+        match meta {
+            Some(Meta::List(list)) => Ok(list),
+            meta => Err(Error::new_spanned(meta, "expected a list")),
+        }
+    }
+
+    fn from_list(list: MetaList) -> Result<Vec<Self>> {
+        Ok(vec![list])
+    }
 }
 
 /// a trait for extracting a value from a literal
@@ -30,23 +105,57 @@ pub trait FromLit: Sized {
     /// # Errors
     /// may return an Error if the literal doesn't contain the correct value
     fn from(lit: Option<Lit>) -> Result<Self>;
+
+    /// extract the value from a value that may not be a literal eg `-4`, `Json` or `Vec<u8>`
+    ///
+    /// By default only literals are accepted.
+    ///
+    /// # Errors
+    /// may return an Error if the value doesn't contain the correct value
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Lit(lit) => <Self as FromLit>::from(Some(lit)),
+            value => Err(Error::new_spanned(value, "expected literal")),
+        }
+    }
 }
 
 impl<F: FromLit> FromMeta for F {
     fn from(meta: Option<Meta>) -> Result<Self> {
-        let lit = meta
-            .map(|m| match m {
-                Meta::NameValue(mnv) => Ok(mnv.lit),
-                _ => Err(Error::new_spanned(m, "expected named value")),
-            })
-            .transpose()?;
-        Self::from(lit)
+        match meta {
+            Some(Meta::NameValue(mnv)) => Self::from_value(mnv.value),
+            Some(meta) => Err(Error::new_spanned(meta, "expected named value")),
+            None => Self::from(None),
+        }
+    }
+
+    /// every element is a value eg `Clone` and `Debug` in `derive(Clone, Debug)`
+    fn from_list(list: MetaList) -> Result<Vec<Self>> {
+        list.nested
+            .into_iter()
+            .map(|nested| Self::from_value(nested.into_value()))
+            .collect()
+    }
+
+    fn from_list_spanned(list: MetaList) -> Result<Vec<(Self, Span)>> {
+        element_spans(list, <Self as FromMeta>::from_list)
+    }
+}
+
+impl FromLit for Lit {
+    fn from(lit: Option<Lit>) -> Result<Self> {
+        lit.ok_or_else(|| Error::new(Span::call_site(), "expected literal"))
     }
 }
 
-impl FromLit for Option<Lit> {
+impl FromLit for Value {
     fn from(lit: Option<Lit>) -> Result<Self> {
-        Ok(lit)
+        lit.map(Value::Lit)
+            .ok_or_else(|| Error::new(Span::call_site(), "expected value"))
+    }
+
+    fn from_value(value: Value) -> Result<Self> {
+        Ok(value)
     }
 }
 
@@ -72,10 +181,10 @@ impl FromLit for Vec<u8> {
 
 impl FromLit for u8 {
     fn from(lit: Option<Lit>) -> Result<Self> {
-        if let Some(Lit::Byte(v)) = lit {
-            Ok(v.value())
-        } else {
-            Err(Error::new_spanned(lit, "expected byte"))
+        match &lit {
+            Some(Lit::Byte(v)) => Ok(v.value()),
+            Some(Lit::Int(v)) => parse_int(v, "u8"),
+            _ => Err(Error::new_spanned(lit, "expected u8")),
         }
     }
 }
@@ -90,42 +199,459 @@ impl FromLit for char {
     }
 }
 
-impl FromLit for i32 {
+impl FromLit for LitStr {
     fn from(lit: Option<Lit>) -> Result<Self> {
-        if let Some(Lit::Int(v)) = &lit {
-            v.base10_parse()
+        if let Some(Lit::Str(v)) = lit {
+            Ok(v)
         } else {
-            Err(Error::new_spanned(lit, "expected i32"))
+            Err(Error::new_spanned(lit, "expected string"))
         }
     }
 }
 
-impl FromLit for f32 {
+macro_rules! impl_from_lit_parse {
+    ($($ty:ty => $name:literal),*) => {
+        $(
+            /// parsed from the content of a string eg `"..."`, errors point into the string
+            ///
+            /// Values that aren't literals are parsed directly eg `Vec<u8>` instead of `"Vec<u8>"`.
+            impl FromLit for $ty {
+                fn from(lit: Option<Lit>) -> Result<Self> {
+                    if let Some(Lit::Str(v)) = &lit {
+                        v.parse()
+                    } else {
+                        Err(Error::new_spanned(lit, concat!("expected string containing ", $name)))
+                    }
+                }
+
+                fn from_value(value: Value) -> Result<Self> {
+                    match value {
+                        Value::Lit(lit) => <Self as FromLit>::from(Some(lit)),
+                        value => parse2(value.into_token_stream()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_lit_parse!(
+    Path => "a path",
+    Ident => "an identifier",
+    syn::Type => "a type",
+    Expr => "an expression",
+    WherePredicate => "a where predicate"
+);
+
+/// parsed from the content of a string eg `"T: Clone, U: Copy"`, errors point into the string
+impl FromLit for Punctuated<WherePredicate, Comma> {
     fn from(lit: Option<Lit>) -> Result<Self> {
-        if let Some(Lit::Float(v)) = &lit {
-            v.base10_parse()
+        if let Some(Lit::Str(v)) = &lit {
+            v.parse_with(Punctuated::parse_terminated)
         } else {
-            Err(Error::new_spanned(lit, "expected f32"))
+            Err(Error::new_spanned(
+                lit,
+                "expected string containing where predicates",
+            ))
+        }
+    }
+
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Lit(lit) => <Self as FromLit>::from(Some(lit)),
+            value => Punctuated::parse_terminated.parse2(value.into_token_stream()),
         }
     }
 }
 
-impl FromLit for bool {
-    fn from(lit: Option<Lit>) -> Result<Self> {
-        if let Some(Lit::Bool(v)) = lit {
-            Ok(v.value)
-        } else {
-            Err(Error::new_spanned(lit, "expected bool"))
+/// parse a value from a string literal with one of the names in `table` eg `"first"` for `Duplicates::First`
+///
+/// This isn't part of the public API, it's only public for `macro-input-macros`.
+///
+/// ```
+/// # use macro_input_core as macro_input;
+/// use macro_input::from_str_table;
+/// use syn::parse_quote;
+///
+/// const LEVELS: &[(&str, u8)] = &[("low", 0), ("high", 1)];
+/// assert_eq!(from_str_table(Some(parse_quote!("high")), LEVELS).unwrap(), 1);
+/// let err = from_str_table(Some(parse_quote!("medium")), LEVELS).unwrap_err();
+/// assert_eq!(err.to_string(), r#"expected one of: "low", "high""#);
+/// ```
+///
+/// # Errors
+/// returns an error if the literal isn't a string or none of the names
+#[doc(hidden)]
+pub fn from_str_table<T: Copy>(lit: Option<Lit>, table: &[(&str, T)]) -> Result<T> {
+    if let Some(Lit::Str(v)) = &lit {
+        let value = v.value();
+        table
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|(_, value)| *value)
+            .ok_or_else(|| {
+                let expected = table
+                    .iter()
+                    .map(|(name, _)| format!("{:?}", name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Error::new_spanned(v, format!("expected one of: {}", expected))
+            })
+    } else {
+        Err(Error::new_spanned(lit, "expected string"))
+    }
+}
+
+/// parse an integer literal and report values out of range for `ty`
+fn parse_int<N>(lit: &LitInt, ty: &str) -> Result<N>
+where
+    N: FromStr + Bounded,
+    N::Err: Display,
+{
+    if !lit.suffix().is_empty() && lit.suffix() != ty {
+        return Err(Error::new(
+            lit.span(),
+            format!("expected {}, got {}", ty, lit.suffix()),
+        ));
+    }
+
+    lit.base10_parse().map_err(|_| {
+        Error::new(
+            lit.span(),
+            format!("expected {} in range {}..={}", ty, N::MIN, N::MAX),
+        )
+    })
+}
+
+/// parse a float or integer literal and report values out of range for `ty`
+fn parse_float<N>(lit: &Lit, ty: &str) -> Result<N>
+where
+    N: FromStr + Bounded + Float,
+    N::Err: Display,
+{
+    let (value, suffix) = match lit {
+        Lit::Float(v) => (v.base10_parse::<N>(), v.suffix()),
+        Lit::Int(v) => (v.base10_parse::<N>(), v.suffix()),
+        _ => unreachable!(),
+    };
+
+    if !suffix.is_empty() && suffix != ty {
+        return Err(Error::new(
+            lit.span(),
+            format!("expected {}, got {}", ty, suffix),
+        ));
+    }
+
+    match value {
+        Ok(value) if value.is_finite() => Ok(value),
+        _ => Err(Error::new(
+            lit.span(),
+            format!("expected {} in range {}..={}", ty, N::MIN, N::MAX),
+        )),
+    }
+}
+
+/// numeric types with a minimum and a maximum value
+trait Bounded: Display {
+    const MIN: Self;
+    const MAX: Self;
+}
+
+/// the name of the constant in a path to a non-finite float eg `NAN` in `f64::NAN`
+fn non_finite_name<'a>(path: &'a Path, ty: &str) -> Option<&'a str> {
+    if path.leading_colon.is_some() || path.segments.len() != 2 || path.segments[0].ident != ty {
+        return None;
+    }
+    ["NAN", "INFINITY", "NEG_INFINITY"]
+        .iter()
+        .copied()
+        .find(|name| path.segments[1].ident == name)
+}
+
+/// floating point types
+trait Float {
+    fn is_finite(&self) -> bool;
+}
+
+macro_rules! impl_from_lit_int {
+    ($($ty:ident),*) => {
+        $(
+            impl Bounded for $ty {
+                const MIN: Self = $ty::MIN;
+                const MAX: Self = $ty::MAX;
+            }
+
+            impl FromLit for $ty {
+                fn from(lit: Option<Lit>) -> Result<Self> {
+                    if let Some(Lit::Int(v)) = &lit {
+                        parse_int(v, stringify!($ty))
+                    } else {
+                        Err(Error::new_spanned(lit, concat!("expected ", stringify!($ty))))
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_lit_int!(i8, i16, i32, i64, i128, isize, u16, u32, u64, u128, usize);
+
+impl Bounded for u8 {
+    const MIN: Self = u8::MIN;
+    const MAX: Self = u8::MAX;
+}
+
+macro_rules! impl_from_lit_float {
+    ($($ty:ident),*) => {
+        $(
+            impl Bounded for $ty {
+                const MIN: Self = $ty::MIN;
+                const MAX: Self = $ty::MAX;
+            }
+
+            impl Float for $ty {
+                fn is_finite(&self) -> bool {
+                    $ty::is_finite(*self)
+                }
+            }
+
+            /// non-finite values are given as constants eg `f64::NAN`
+            impl FromLit for $ty {
+                fn from(lit: Option<Lit>) -> Result<Self> {
+                    match &lit {
+                        Some(lit @ Lit::Float(_)) | Some(lit @ Lit::Int(_)) => {
+                            parse_float(lit, stringify!($ty))
+                        }
+                        _ => Err(Error::new_spanned(lit, concat!("expected ", stringify!($ty)))),
+                    }
+                }
+
+                fn from_value(value: Value) -> Result<Self> {
+                    match value {
+                        Value::Lit(lit) => <Self as FromLit>::from(Some(lit)),
+                        Value::Expr(Expr::Path(path)) => {
+                            match non_finite_name(&path.path, stringify!($ty)) {
+                                Some("NAN") => Ok($ty::NAN),
+                                Some("INFINITY") => Ok($ty::INFINITY),
+                                Some("NEG_INFINITY") => Ok($ty::NEG_INFINITY),
+                                _ => Err(Error::new_spanned(path, concat!("expected ", stringify!($ty)))),
+                            }
+                        }
+                        value => Err(Error::new_spanned(value, "expected literal")),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_lit_float!(f32, f64);
+
+/// `#[foo(bar)]` and `#[foo(bar = true)]` are true, `#[foo(bar = false)]` and nothing are false
+impl FromMeta for bool {
+    fn from(meta: Option<Meta>) -> Result<Self> {
+        match meta {
+            None => Ok(false),
+            Some(Meta::Path(_)) => Ok(true),
+            Some(Meta::NameValue(MetaNameValue {
+                value: Value::Lit(Lit::Bool(v)),
+                ..
+            })) => Ok(v.value),
+            Some(Meta::NameValue(mnv)) => Err(Error::new_spanned(mnv.value, "expected bool")),
+            Some(meta) => Err(Error::new_spanned(meta, "expected bool")),
         }
     }
+
+    fn from_list_spanned(list: MetaList) -> Result<Vec<(Self, Span)>> {
+        element_spans(list, Self::from_list)
+    }
 }
 
-impl<V: FromLit> FromLit for Option<V> {
-    fn from(lit: Option<Lit>) -> Result<Self> {
-        if lit.is_some() {
-            Some(V::from(lit)).transpose()
+impl<V: FromMeta> FromMeta for Option<V> {
+    fn from(meta: Option<Meta>) -> Result<Self> {
+        if meta.is_some() {
+            Some(V::from(meta)).transpose()
         } else {
             Ok(None)
         }
     }
+
+    fn from_list(list: MetaList) -> Result<Vec<Self>> {
+        V::from_list(list).map(|values| values.into_iter().map(Some).collect())
+    }
+
+    fn from_list_spanned(list: MetaList) -> Result<Vec<(Self, Span)>> {
+        V::from_list_spanned(list).map(|values| {
+            values
+                .into_iter()
+                .map(|(value, span)| (Some(value), span))
+                .collect()
+        })
+    }
+}
+
+/// the reverse of [`FromMeta`]: a trait for turning a value back into a meta
+pub trait ToMeta {
+    /// the meta for the value of a field with the key `path` eg `bar = 3` in `#[foo(bar = 3)]`
+    ///
+    /// Missing values eg `None` don't have a meta.
+    fn to_meta(&self, path: &Path) -> Option<Meta>;
+}
+
+impl ToMeta for Option<()> {
+    fn to_meta(&self, path: &Path) -> Option<Meta> {
+        self.map(|()| Meta::Path(path.clone()))
+    }
+}
+
+impl ToMeta for MetaList {
+    fn to_meta(&self, path: &Path) -> Option<Meta> {
+        Some(Meta::List(MetaList {
+            path: path.clone(),
+            paren_token: self.paren_token,
+            nested: self.nested.clone(),
+        }))
+    }
+}
+
+/// the reverse of [`FromLit`]: a trait for turning a value back into a literal
+///
+/// [`ToMeta`] is automatically implemented for all implementations
+pub trait ToLit {
+    /// the value eg `3` or `"foo"`
+    fn to_value(&self) -> Value;
+}
+
+impl<T: ToLit> ToMeta for T {
+    fn to_meta(&self, path: &Path) -> Option<Meta> {
+        Some(Meta::NameValue(MetaNameValue {
+            path: path.clone(),
+            eq_token: Token![=](Span::call_site()),
+            value: self.to_value(),
+        }))
+    }
+}
+
+impl ToLit for Lit {
+    fn to_value(&self) -> Value {
+        Value::Lit(self.clone())
+    }
+}
+
+impl ToLit for Value {
+    fn to_value(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl ToLit for String {
+    fn to_value(&self) -> Value {
+        Value::Lit(Lit::Str(LitStr::new(self, Span::call_site())))
+    }
+}
+
+impl ToLit for Vec<u8> {
+    fn to_value(&self) -> Value {
+        Value::Lit(Lit::ByteStr(LitByteStr::new(self, Span::call_site())))
+    }
+}
+
+impl ToLit for char {
+    fn to_value(&self) -> Value {
+        Value::Lit(Lit::Char(LitChar::new(*self, Span::call_site())))
+    }
+}
+
+impl ToLit for LitStr {
+    fn to_value(&self) -> Value {
+        Value::Lit(Lit::Str(self.clone()))
+    }
+}
+
+macro_rules! impl_to_lit_parse {
+    ($($ty:ty),*) => {
+        $(
+            /// emitted as a string eg `"Vec<u8>"`
+            impl ToLit for $ty {
+                fn to_value(&self) -> Value {
+                    let tokens = self.to_token_stream().to_string();
+                    Value::Lit(Lit::Str(LitStr::new(&tokens, Span::call_site())))
+                }
+            }
+        )*
+    };
+}
+
+impl_to_lit_parse!(
+    Path,
+    Ident,
+    syn::Type,
+    Expr,
+    WherePredicate,
+    Punctuated<WherePredicate, Comma>
+);
+
+macro_rules! impl_to_lit_int {
+    ($($ty:ident),*) => {
+        $(
+            /// emitted without a suffix eg `3` instead of `3u8`
+            impl ToLit for $ty {
+                fn to_value(&self) -> Value {
+                    Value::Lit(Lit::Int(LitInt::new(&self.to_string(), Span::call_site())))
+                }
+            }
+        )*
+    };
+}
+
+impl_to_lit_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_to_lit_float {
+    ($($ty:ident),*) => {
+        $(
+            /// emitted without a suffix eg `1.5` instead of `1.5f32`, non-finite values as constants eg `f64::NAN`
+            impl ToLit for $ty {
+                fn to_value(&self) -> Value {
+                    if self.is_finite() {
+                        let repr = format!("{:?}", self);
+                        return Value::Lit(Lit::Float(LitFloat::new(&repr, Span::call_site())));
+                    }
+
+                    let ty = Ident::new(stringify!($ty), Span::call_site());
+                    let name = if self.is_nan() {
+                        "NAN"
+                    } else if self.is_sign_positive() {
+                        "INFINITY"
+                    } else {
+                        "NEG_INFINITY"
+                    };
+                    let name = Ident::new(name, Span::call_site());
+                    Value::Expr(parse_quote!(#ty::#name))
+                }
+            }
+        )*
+    };
+}
+
+impl_to_lit_float!(f32, f64);
+
+/// `true` is emitted as a flag eg `#[foo(bar)]`, `false` as `#[foo(bar = false)]`
+impl ToMeta for bool {
+    fn to_meta(&self, path: &Path) -> Option<Meta> {
+        if *self {
+            Some(Meta::Path(path.clone()))
+        } else {
+            Some(Meta::NameValue(MetaNameValue {
+                path: path.clone(),
+                eq_token: Token![=](Span::call_site()),
+                value: Value::Lit(Lit::Bool(LitBool::new(false, Span::call_site()))),
+            }))
+        }
+    }
+}
+
+impl<V: ToMeta> ToMeta for Option<V> {
+    fn to_meta(&self, path: &Path) -> Option<Meta> {
+        self.as_ref().and_then(|value| value.to_meta(path))
+    }
 }
@@ -0,0 +1,72 @@
+use crate::{from_str_table, FromLit};
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{Lit, Result};
+
+/// what to do when a field is given more than once eg `#[foo(bar = 1)] #[foo(bar = 2)]`
+///
+/// Values of repeated fields eg `Vec<T>` are always collected from all occurrences.
+/// # Example
+/// ```
+/// # use macro_input_core as macro_input;
+/// use macro_input::{DefaultValue, Def, Duplicates};
+/// use syn::{parse_quote, Attribute};
+///
+/// # fn main() -> syn::Result<()> {
+/// const BAR_FIELD: Def = Def::new("foo", "bar", false, DefaultValue::U32(None));
+/// const LAST_BAR_FIELD: Def =
+///     Def::new("foo", "bar", false, DefaultValue::U32(None)).with_duplicates(Duplicates::Last);
+///
+/// let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(bar = 1)]), parse_quote!(#[foo(bar = 2)])];
+/// assert!(BAR_FIELD.get_value::<u32>(&attrs).is_err());
+/// assert_eq!(LAST_BAR_FIELD.get_value::<u32>(&attrs)?, 2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Duplicates {
+    /// giving the field more than once is an error
+    Error,
+    /// the first occurrence is used
+    First,
+    /// the last occurrence is used
+    Last,
+    /// all occurrences are merged into one list eg `bar(a = 1, b = 2)` for `#[foo(bar(a = 1))] #[foo(bar(b = 2))]`
+    ///
+    /// The values of name-value pairs become elements of the list eg `bar(1, 2)` for `#[foo(bar = 1, bar = 2)]`,
+    /// a field that's given once is left as it is eg `bar = 1`. Flags have no values to merge, giving them more than once is still an error.
+    Merge,
+}
+
+/// giving a field more than once is an error unless it's allowed
+impl Default for Duplicates {
+    fn default() -> Self {
+        Duplicates::Error
+    }
+}
+
+const POLICIES: &[(&str, Duplicates)] = &[
+    ("error", Duplicates::Error),
+    ("first", Duplicates::First),
+    ("last", Duplicates::Last),
+    ("merge", Duplicates::Merge),
+];
+
+/// parsed from `"error"`, `"first"`, `"last"` or `"merge"`
+impl FromLit for Duplicates {
+    fn from(lit: Option<Lit>) -> Result<Self> {
+        from_str_table(lit, POLICIES)
+    }
+}
+
+impl ToTokens for Duplicates {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let tts = match self {
+            Duplicates::Error => quote!(::macro_input::Duplicates::Error),
+            Duplicates::First => quote!(::macro_input::Duplicates::First),
+            Duplicates::Last => quote!(::macro_input::Duplicates::Last),
+            Duplicates::Merge => quote!(::macro_input::Duplicates::Merge),
+        };
+        tts.to_tokens(tokens);
+    }
+}
@@ -1,15 +1,21 @@
-use crate::{convert::FromMeta, DefaultValue};
+use crate::{
+    convert::{FromMeta, ToMeta},
+    parse_meta, DefaultValue, Defs, Duplicates, Flag, Meta, MetaList, MetaNameValue, NestedMeta,
+    Partial, Types, Value,
+};
 #[cfg(feature = "legacy")]
 use macro_compose::{Collector, Context, Lint};
 use proc_macro2::Span;
 use quote::{format_ident, ToTokens};
-use std::{iter::FromIterator, mem::replace};
+use std::{iter::FromIterator, mem::take};
 use syn::{
-    parse::Parse, parse2, parse_quote, punctuated::Punctuated, Attribute, Error, Lit, Meta,
-    NestedMeta, Result,
+    parse::Parse, parse2, parse_quote, punctuated::Punctuated, spanned::Spanned, token::Paren,
+    Attribute, Error, Expr, Lit, LitBool, Path, Result, Token,
 };
 
 /// a field definition
+///
+/// Options other than the ones passed to [`Def::new`] are set with the const builders eg [`Def::with_aliases`].
 /// # Example
 /// ```
 /// # use macro_input_core as macro_input;
@@ -27,6 +33,30 @@ pub struct Def<'a> {
     pub required: bool,
     /// the typed default value
     pub default: DefaultValue,
+    /// the index of the leading value this field can be given as eg `0` for `"/"` in `#[route("/", method = "GET")]`
+    pub(crate) position: Option<usize>,
+    /// other paths the field is accepted under eg `mm` in `#[mm(bar = 3)]`
+    pub(crate) path_aliases: &'a [&'a str],
+    /// other names the field is accepted under eg an old name
+    pub(crate) aliases: &'a [&'a str],
+    /// the fields that are parsed from the same attributes instead of a key for this field
+    pub(crate) flattened: Option<&'a Defs<'a>>,
+    /// the description of the field in the help text eg its doc comment
+    pub(crate) doc: &'a str,
+    /// the name of the type of the value in the help text eg `i32`, by default the name of the type of the default value is used
+    pub(crate) type_name: &'a str,
+    /// whether or not the field is left out of the help text
+    pub(crate) hidden: bool,
+    /// examples for the field in the help text eg `timeout = 5`
+    pub(crate) examples: &'a [&'a str],
+    /// what to do when the field is given more than once
+    pub(crate) duplicates: Duplicates,
+    /// the names of the fields that can't be given together with this field
+    pub(crate) conflicts_with: &'a [&'a str],
+    /// the names of the fields that have to be given together with this field
+    pub(crate) requires: &'a [&'a str],
+    /// the names and values of other fields that make this field required eg `("method", "\"POST\"")`
+    pub(crate) required_if: &'a [(&'a str, &'a str)],
 }
 
 impl<'a> Def<'a> {
@@ -38,6 +68,315 @@ impl<'a> Def<'a> {
             name,
             required,
             default,
+            position: None,
+            path_aliases: &[],
+            aliases: &[],
+            flattened: None,
+            doc: "",
+            type_name: "",
+            hidden: false,
+            examples: &[],
+            duplicates: Duplicates::Error,
+            conflicts_with: &[],
+            requires: &[],
+            required_if: &[],
+        }
+    }
+
+    /// also accept the field as the leading value at `index`
+    ///
+    /// Leading values are the values in front of the first key eg `"/users"` in `#[route("/users", method = "GET")]`.
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def};
+    /// use syn::{parse_quote, Attribute};
+    ///
+    /// # fn main() -> syn::Result<()> {
+    /// const PATH_FIELD: Def =
+    ///     Def::new("route", "path", true, DefaultValue::Str(None)).with_position(0);
+    /// const METHOD_FIELD: Def = Def::new("route", "method", true, DefaultValue::Str(None));
+    ///
+    /// let attr: Attribute = parse_quote!(#[route("/users", method = "GET")]);
+    /// assert_eq!(PATH_FIELD.get_value::<String>(&[attr.clone()])?, "/users");
+    /// assert_eq!(METHOD_FIELD.get_value::<String>(&[attr])?, "GET");
+    ///
+    /// let attr: Attribute = parse_quote!(#[route(path = "/users", method = "GET")]);
+    /// assert_eq!(PATH_FIELD.get_value::<String>(&[attr])?, "/users");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_position(mut self, index: usize) -> Self {
+        self.position = Some(index);
+        self
+    }
+
+    /// also accept the field under other names
+    ///
+    /// Giving the field under more than one of its names is an error.
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def};
+    /// use syn::{parse_quote, Attribute};
+    ///
+    /// # fn main() -> syn::Result<()> {
+    /// const SIZE_FIELD: Def =
+    ///     Def::new("foo", "size", true, DefaultValue::U32(None)).with_aliases(&["len"]);
+    ///
+    /// let attr: Attribute = parse_quote!(#[foo(len = 3)]);
+    /// assert_eq!(SIZE_FIELD.get_value::<u32>(&[attr])?, 3);
+    ///
+    /// let attr: Attribute = parse_quote!(#[foo(size = 3, len = 4)]);
+    /// assert!(SIZE_FIELD.get_value::<u32>(&[attr]).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_aliases(mut self, aliases: &'a [&'a str]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// also accept the field in attributes with other paths
+    ///
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def};
+    /// use syn::{parse_quote, Attribute};
+    ///
+    /// # fn main() -> syn::Result<()> {
+    /// const SIZE_FIELD: Def =
+    ///     Def::new("my_macro", "size", true, DefaultValue::U32(None)).with_path_aliases(&["mm"]);
+    ///
+    /// let attr: Attribute = parse_quote!(#[mm(size = 3)]);
+    /// assert_eq!(SIZE_FIELD.get_value::<u32>(&[attr])?, 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_path_aliases(mut self, path_aliases: &'a [&'a str]) -> Self {
+        self.path_aliases = path_aliases;
+        self
+    }
+
+    /// parse the value of this field from the keys of other fields in the same attributes
+    ///
+    /// The value is parsed from a list of all keys that belong to `defs` eg `bar(baz = 1)` for `#[foo(baz = 1, other = 2)]`.
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def, Defs, MetaList};
+    /// use syn::{parse_quote, Attribute};
+    ///
+    /// # fn main() -> syn::Result<()> {
+    /// const DEBUG_FIELD: Def = Def::new("common", "debug", false, DefaultValue::Flag);
+    /// const COMMON_FIELDS: &[&Def] = &[&DEBUG_FIELD];
+    /// const COMMON_FIELD_DEFS: Defs = Defs::new(COMMON_FIELDS);
+    ///
+    /// const COMMON_FIELD: Def =
+    ///     Def::new("foo", "common", false, DefaultValue::Any(None)).with_flattened(&COMMON_FIELD_DEFS);
+    ///
+    /// let attr: Attribute = parse_quote!(#[foo(debug, other = 2)]);
+    /// let list = COMMON_FIELD.get_value::<MetaList>(&[attr])?;
+    /// assert_eq!(list.nested.len(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn with_flattened(mut self, defs: &'a Defs<'a>) -> Self {
+        self.flattened = Some(defs);
+        self
+    }
+
+    /// choose what to do when the field is given more than once, by default it's an error
+    #[must_use]
+    pub const fn with_duplicates(mut self, duplicates: Duplicates) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// forbid giving the field together with other fields
+    ///
+    /// This and the other constraints between fields are checked by [`Defs::check_constraints`].
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def, Defs};
+    /// use syn::{parse_quote, Attribute};
+    ///
+    /// const SYNC_FIELD: Def =
+    ///     Def::new("foo", "sync", false, DefaultValue::Flag).with_conflicts_with(&["async"]);
+    /// const ASYNC_FIELD: Def = Def::new("foo", "async", false, DefaultValue::Flag);
+    /// const FOO_FIELDS: &[&Def] = &[&SYNC_FIELD, &ASYNC_FIELD];
+    /// const FOO_FIELD_DEFS: Defs = Defs::new(FOO_FIELDS);
+    ///
+    /// let attr: Attribute = parse_quote!(#[foo(sync)]);
+    /// assert!(FOO_FIELD_DEFS.check_constraints(&[attr]).is_ok());
+    /// let attr: Attribute = parse_quote!(#[foo(sync, async)]);
+    /// let err = FOO_FIELD_DEFS.check_constraints(&[attr]).unwrap_err();
+    /// assert_eq!(err.to_string(), "sync can't be combined with async");
+    /// ```
+    #[must_use]
+    pub const fn with_conflicts_with(mut self, conflicts_with: &'a [&'a str]) -> Self {
+        self.conflicts_with = conflicts_with;
+        self
+    }
+
+    /// require other fields if the field is given eg `key` for `cert`
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def, Defs};
+    /// use syn::{parse_quote, Attribute};
+    ///
+    /// const CERT_FIELD: Def =
+    ///     Def::new("tls", "cert", false, DefaultValue::Str(None)).with_requires(&["key"]);
+    /// const KEY_FIELD: Def = Def::new("tls", "key", false, DefaultValue::Str(None));
+    /// const TLS_FIELDS: &[&Def] = &[&CERT_FIELD, &KEY_FIELD];
+    /// const TLS_FIELD_DEFS: Defs = Defs::new(TLS_FIELDS);
+    ///
+    /// let attr: Attribute = parse_quote!(#[tls(cert = "a.pem", key = "a.key")]);
+    /// assert!(TLS_FIELD_DEFS.check_constraints(&[attr]).is_ok());
+    /// let attr: Attribute = parse_quote!(#[tls(cert = "a.pem")]);
+    /// let err = TLS_FIELD_DEFS.check_constraints(&[attr]).unwrap_err();
+    /// assert_eq!(err.to_string(), "cert requires key");
+    /// ```
+    #[must_use]
+    pub const fn with_requires(mut self, requires: &'a [&'a str]) -> Self {
+        self.requires = requires;
+        self
+    }
+
+    /// require the field if other fields have a value eg `&[("method", "\"POST\"")]`
+    ///
+    /// The values are in their source form, flags have the value `true`.
+    /// Fields that aren't given are compared by their default value.
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def, Defs};
+    /// use syn::{parse_quote, Attribute};
+    ///
+    /// const METHOD_FIELD: Def = Def::new("route", "method", true, DefaultValue::Str(None));
+    /// const BODY_FIELD: Def = Def::new("route", "body", false, DefaultValue::Str(None))
+    ///     .with_required_if(&[("method", "\"POST\"")]);
+    /// const ROUTE_FIELDS: &[&Def] = &[&METHOD_FIELD, &BODY_FIELD];
+    /// const ROUTE_FIELD_DEFS: Defs = Defs::new(ROUTE_FIELDS);
+    ///
+    /// let attr: Attribute = parse_quote!(#[route(method = "GET")]);
+    /// assert!(ROUTE_FIELD_DEFS.check_constraints(&[attr]).is_ok());
+    /// let attr: Attribute = parse_quote!(#[route(method = "POST")]);
+    /// let err = ROUTE_FIELD_DEFS.check_constraints(&[attr]).unwrap_err();
+    /// assert_eq!(err.to_string(), r#"body is required if method = "POST""#);
+    /// ```
+    #[must_use]
+    pub const fn with_required_if(mut self, required_if: &'a [(&'a str, &'a str)]) -> Self {
+        self.required_if = required_if;
+        self
+    }
+
+    /// describe the field in the help text eg `request timeout`
+    ///
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def, Defs};
+    ///
+    /// const TIMEOUT_FIELD: Def = Def::new("foo", "timeout", false, DefaultValue::I32(Some(3)))
+    ///     .with_doc("request timeout")
+    ///     .with_examples(&["timeout = 5"]);
+    /// const SECRET_FIELD: Def = Def::new("foo", "secret", false, DefaultValue::Flag).with_hidden(true);
+    /// const FOO_FIELDS: &[&Def] = &[&TIMEOUT_FIELD, &SECRET_FIELD];
+    /// const FOO_FIELD_DEFS: Defs = Defs::new(FOO_FIELDS);
+    ///
+    /// assert_eq!(
+    ///     FOO_FIELD_DEFS.help(),
+    ///     "`timeout = <i32>` (default 3): request timeout\n    eg `timeout = 5`"
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn with_doc(mut self, doc: &'a str) -> Self {
+        self.doc = doc;
+        self
+    }
+
+    /// name the type of the value in the help text eg `Duration`
+    #[must_use]
+    pub const fn with_type_name(mut self, type_name: &'a str) -> Self {
+        self.type_name = type_name;
+        self
+    }
+
+    /// leave the field out of the help text
+    #[must_use]
+    pub const fn with_hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// show examples for the field in the help text eg `timeout = 5`
+    #[must_use]
+    pub const fn with_examples(mut self, examples: &'a [&'a str]) -> Self {
+        self.examples = examples;
+        self
+    }
+
+    /// checks whether both fields accept a common key
+    ///
+    /// This is used to detect flattened fields that conflict with other fields at compile time.
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def};
+    ///
+    /// const SIZE_FIELD: Def = Def::new("foo", "size", false, DefaultValue::U32(None));
+    /// const LEN_FIELD: Def = Def::new("foo", "len", false, DefaultValue::U32(None));
+    /// const OLD_LEN_FIELD: Def =
+    ///     Def::new("foo", "length", false, DefaultValue::U32(None)).with_aliases(&["size"]);
+    ///
+    /// assert!(!SIZE_FIELD.shares_key(&LEN_FIELD));
+    /// assert!(SIZE_FIELD.shares_key(&OLD_LEN_FIELD));
+    /// ```
+    #[must_use]
+    pub const fn shares_key(&self, other: &Def) -> bool {
+        if let Some(defs) = self.flattened {
+            return defs.shares_key(other);
+        }
+        if let Some(defs) = other.flattened {
+            return defs.shares_key(self);
+        }
+
+        let mut i = 0;
+        while i <= self.aliases.len() {
+            let key = if i == 0 {
+                self.name
+            } else {
+                self.aliases[i - 1]
+            };
+            let mut j = 0;
+            while j <= other.aliases.len() {
+                let other_key = if j == 0 {
+                    other.name
+                } else {
+                    other.aliases[j - 1]
+                };
+                if is_key(key) && str_eq(key, other_key) {
+                    return true;
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+
+        false
+    }
+
+    /// checks whether the path of an attribute is the path of the field or one of its aliases
+    pub(crate) fn matches_path(&self, path: &Path) -> bool {
+        path.is_ident(self.path) || self.path_aliases.iter().any(|alias| path.is_ident(alias))
+    }
+
+    /// checks whether the path of a meta is the name of the field or one of its aliases
+    pub(crate) fn matches_name(&self, path: &Path) -> bool {
+        match self.flattened {
+            Some(defs) => defs.matches_name(path),
+            None => {
+                path.is_ident(self.name) || self.aliases.iter().any(|alias| path.is_ident(alias))
+            }
         }
     }
 
@@ -62,91 +401,478 @@ impl<'a> Def<'a> {
     /// assert_eq!(attrs, vec![other_attr]);
     /// ```
     pub fn strip(&self, attrs: &mut Vec<Attribute>) {
-        let data = replace(attrs, Vec::new());
-        attrs.extend(data.into_iter().filter_map(|mut a| {
-            if self.strip_from_attribute(&mut a) {
-                None
-            } else {
-                Some(a)
-            }
-        }));
+        strip(&[self], attrs);
     }
 
-    /// strip the attribute and return whether it was empty
-    fn strip_from_attribute(&self, attr: &mut Attribute) -> bool {
-        let mut meta = if let Ok(meta) = attr.parse_meta() {
-            meta
-        } else {
-            return false;
-        };
+    /// try to find the meta that has the value for this field
+    ///
+    /// If the field is required but not found the error points at the attribute with the path of the field eg
+    /// `#[foo(baz = 1)]` when looking for `bar`.
+    ///
+    /// # Errors
+    /// may return the error if the field is required but not found
+    pub fn get_meta(&self, attrs: &[Attribute]) -> Result<Option<Meta>> {
+        self.get_meta_spanned(attrs, Span::call_site())
+    }
 
-        // check the path
-        if !meta.path().is_ident(self.path) {
-            return false;
+    /// try to find the meta that has the value for this field, errors for a missing required field point at `span`
+    /// eg the ident of the item unless there's an attribute with the path of the field
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def};
+    /// use proc_macro2::Span;
+    ///
+    /// const BAR_FIELD: Def = Def::new("foo", "bar", true, DefaultValue::U32(None));
+    /// let err = BAR_FIELD.get_meta_spanned(&[], Span::call_site()).unwrap_err();
+    /// assert_eq!(err.to_string(), "attribute for required field not found: foo::bar");
+    /// ```
+    ///
+    /// # Errors
+    /// may return the error if the field is required but not found
+    pub fn get_meta_spanned(&self, attrs: &[Attribute], span: Span) -> Result<Option<Meta>> {
+        if let Some(meta) = self.find_meta(attrs)? {
+            return Ok(Some(meta));
         }
 
-        match &mut meta {
-            Meta::List(list) => {
-                let new_punctuated = list
-                    .nested
-                    .iter()
-                    .filter(|meta| {
-                        if let NestedMeta::Meta(meta) = meta {
-                            !meta.path().is_ident(self.name)
-                        } else {
-                            true
-                        }
-                    })
-                    .cloned();
-                list.nested = Punctuated::from_iter(new_punctuated);
+        if self.required {
+            return Err(self.missing_error(
+                attrs,
+                span,
+                format!(
+                    "attribute for required field not found: {}::{}",
+                    self.path, self.name
+                ),
+            ));
+        }
 
-                let empty = list.nested.is_empty();
-                *attr = parse_quote!(#[#list]);
-                empty
-            }
-            Meta::Path(_) => true,
-            _ => false,
+        self.default_meta()
+    }
+
+    /// an error at the first attribute with the path of the field or at `span` if there is none
+    fn missing_error(&self, attrs: &[Attribute], span: Span, message: String) -> Error {
+        match attrs.iter().find(|attr| self.matches_path(&attr.path)) {
+            Some(attr) => Error::new_spanned(attr, message),
+            None => Error::new(span, message),
         }
     }
 
-    /// try to find the meta that has the value for this field
+    /// try to find the meta that has the value for this field in a nested meta list eg `bar(baz = 1)` in `#[foo(bar(baz = 1))]`
     ///
     /// # Errors
     /// may return the error if the field is required but not found
-    pub fn get_meta(&self, attrs: &[Attribute]) -> Result<Option<Meta>> {
-        for attr in attrs.iter() {
-            let meta = attr.parse_meta()?;
-            if meta.path().is_ident(self.path) {
-                if let Meta::List(list) = meta {
-                    for meta in list.nested.iter() {
-                        if let NestedMeta::Meta(meta) = meta {
-                            if meta.path().is_ident(self.name) {
-                                return Ok(Some(meta.clone()));
-                            }
+    pub fn get_nested_meta(&self, list: &MetaList) -> Result<Option<Meta>> {
+        if let Some(meta) = self.find_nested_meta(list)? {
+            return Ok(Some(meta));
+        }
+
+        if self.required {
+            return Err(Error::new_spanned(
+                list,
+                format!(
+                    "field required by {} not found: {}",
+                    list.path.to_token_stream(),
+                    self.name
+                ),
+            ));
+        }
+
+        self.default_meta()
+    }
+
+    /// the span of the meta for this field if it is given eg for validating its value after parsing
+    ///
+    /// # Errors
+    /// may return an error if the field is given more than once or under more than one name
+    pub fn value_span(&self, attrs: &[Attribute]) -> Result<Option<Span>> {
+        Ok(self.find_meta(attrs)?.map(|meta| meta.span()))
+    }
+
+    /// the span of the meta for this field in a nested meta list if it is given
+    ///
+    /// # Errors
+    /// may return an error if the field is given more than once or under more than one name
+    pub fn nested_value_span(&self, list: &MetaList) -> Result<Option<Span>> {
+        Ok(self.find_nested_meta(list)?.map(|meta| meta.span()))
+    }
+
+    /// find the meta for this field without falling back to the default value
+    pub(crate) fn find_meta(&self, attrs: &[Attribute]) -> Result<Option<Meta>> {
+        let metas = self.get_all_metas(attrs)?;
+        if self.flattened.is_some() {
+            let path = format_ident!("{}", self.path).into();
+            return Ok(Some(flattened_list(path, metas)));
+        }
+        self.select(metas)
+    }
+
+    /// find the meta for this field in a nested meta list without falling back to the default value
+    pub(crate) fn find_nested_meta(&self, list: &MetaList) -> Result<Option<Meta>> {
+        let metas = self.get_all_nested_metas(list);
+        if self.flattened.is_some() {
+            return Ok(Some(flattened_list(list.path.clone(), metas)));
+        }
+        self.select(metas)
+    }
+
+    /// select the meta for this field out of all occurrences according to [`duplicates`](Self::duplicates)
+    ///
+    /// With [`Duplicates::Merge`] a single occurrence is returned as it is, only repeated occurrences become a list.
+    fn select(&self, metas: Vec<Meta>) -> Result<Option<Meta>> {
+        self.check_aliases(&metas)?;
+
+        let mut metas = metas.into_iter();
+        match self.duplicates {
+            Duplicates::Error => {
+                let first = metas.next();
+                if let Some(first) = &first {
+                    let mut error: Option<Error> = None;
+                    for duplicate in metas {
+                        let e = self.duplicate_error(first, &duplicate);
+                        match &mut error {
+                            Some(error) => error.combine(e),
+                            None => error = Some(e),
+                        }
+                    }
+                    if let Some(error) = error {
+                        return Err(error);
+                    }
+                }
+                Ok(first)
+            }
+            Duplicates::First => Ok(metas.next()),
+            Duplicates::Last => Ok(metas.last()),
+            Duplicates::Merge => {
+                let first = match metas.next() {
+                    Some(first) => first,
+                    None => return Ok(None),
+                };
+                let rest = metas.collect::<Vec<_>>();
+                if rest.is_empty() {
+                    return Ok(Some(first));
+                }
+
+                let path = first.path().clone();
+                let mut nested = Punctuated::new();
+                for meta in std::iter::once(first).chain(rest) {
+                    match meta {
+                        Meta::List(list) => nested.extend(list.nested),
+                        Meta::NameValue(mnv) => nested.push(NestedMeta::Value(mnv.value)),
+                        Meta::Path(path) => {
+                            return Err(Error::new_spanned(
+                                path,
+                                format!("duplicate {} attribute", self.name),
+                            ))
                         }
                     }
                 }
+                Ok(Some(Meta::List(MetaList {
+                    path,
+                    paren_token: Paren::default(),
+                    nested,
+                })))
             }
         }
+    }
 
-        if self.required {
-            return Err(Error::new(
-                Span::call_site(),
+    /// the error for a field that is given more than once, pointing at both occurrences
+    fn duplicate_error(&self, first: &Meta, duplicate: &Meta) -> Error {
+        let mut error = Error::new_spanned(
+            duplicate.path(),
+            format!("duplicate {} attribute", self.name),
+        );
+        error.combine(Error::new_spanned(
+            first.path(),
+            format!("{} is first given here", self.name),
+        ));
+        error
+    }
+
+    /// check that the field isn't given under more than one of its names eg its name and an alias
+    fn check_aliases(&self, metas: &[Meta]) -> Result<()> {
+        let name = |meta: &Meta| meta.path().to_token_stream().to_string();
+        let mut metas = metas.iter();
+        let first = match metas.next() {
+            Some(first) => name(first),
+            None => return Ok(()),
+        };
+
+        match metas.find(|meta| name(meta) != first) {
+            Some(meta) => Err(Error::new_spanned(
+                meta.path(),
                 format!(
-                    "attribute for required field not found: {}::{}",
-                    self.path, self.name
+                    "duplicate {} attribute: {} and {} are the same field",
+                    self.name,
+                    first,
+                    name(meta)
                 ),
-            ));
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// find all metas for this field in the order they appear
+    ///
+    /// # Errors
+    /// may return an error if an attribute can't be parsed
+    pub fn get_all_metas(&self, attrs: &[Attribute]) -> Result<Vec<Meta>> {
+        let mut metas = Vec::new();
+        for attr in attrs.iter().filter(|attr| self.matches_path(&attr.path)) {
+            if let Meta::List(list) = parse_meta(attr)? {
+                metas.extend(self.find_all_nested_metas(&list));
+            }
+        }
+        Ok(metas)
+    }
+
+    /// find all metas for this field in a nested meta list in the order they appear
+    #[must_use]
+    pub fn get_all_nested_metas(&self, list: &MetaList) -> Vec<Meta> {
+        self.find_all_nested_metas(list).collect()
+    }
+
+    fn find_all_nested_metas<'b>(&'b self, list: &'b MetaList) -> impl Iterator<Item = Meta> + 'b {
+        let positional = self
+            .position
+            .and_then(|index| leading_values(list).nth(index))
+            .map(|value| {
+                Meta::NameValue(MetaNameValue {
+                    path: self.key(),
+                    eq_token: Token![=](value.span()),
+                    value: value.clone(),
+                })
+            });
+
+        let keyed = list.nested.iter().filter_map(move |meta| match meta {
+            NestedMeta::Meta(meta) if self.matches_name(meta.path()) => Some(meta.clone()),
+            _ => None,
+        });
+
+        positional.into_iter().chain(keyed)
+    }
+
+    /// construct a meta from the default value
+    pub(crate) fn default_meta(&self) -> Result<Option<Meta>> {
+        Ok(self.default.as_value()?.map(|value| {
+            Meta::NameValue(MetaNameValue {
+                path: self.key(),
+                eq_token: Default::default(),
+                value,
+            })
+        }))
+    }
+
+    /// the key of the field or the path for fields that can only be given positionally eg `0` of a tuple struct
+    #[must_use]
+    pub fn key(&self) -> Path {
+        let key = if is_key(self.name) {
+            self.name
+        } else {
+            self.path
+        };
+        format_ident!("{}", key).into()
+    }
+
+    /// the reverse of [`get_nested_value`](Self::get_nested_value): the argument for a value of this field eg `bar = 3`
+    ///
+    /// Values of fields that can only be given positionally eg `0` of a tuple struct are leading values.
+    /// If `omit_default` is set values that are the same as the default value are omitted.
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def};
+    /// use quote::quote;
+    ///
+    /// const BAR_FIELD: Def = Def::new("foo", "bar", false, DefaultValue::U32(Some(3)));
+    /// let nested = BAR_FIELD.to_nested(&4u32, true);
+    /// assert_eq!(quote!(#nested).to_string(), "bar = 4");
+    /// assert!(BAR_FIELD.to_nested(&3u32, true).is_none());
+    /// assert!(BAR_FIELD.to_nested(&None::<u32>, false).is_none());
+    /// ```
+    pub fn to_nested<V: ToMeta>(&self, value: &V, omit_default: bool) -> Option<NestedMeta> {
+        let meta = value.to_meta(&self.key())?;
+        if omit_default && self.is_default(&meta) {
+            return None;
+        }
+
+        match meta {
+            Meta::NameValue(mnv) if !is_key(self.name) => Some(NestedMeta::Value(mnv.value)),
+            meta => Some(NestedMeta::Meta(meta)),
+        }
+    }
+
+    /// convert a value to a nested meta for this field while emitting the fields of an input in order
+    ///
+    /// `leading` tracks whether leading values may still be emitted: positional fields are emitted as
+    /// leading values until one of them is omitted or a key is emitted, after that they're emitted by
+    /// their keys. Values of fields that can only be given positionally are never omitted.
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def};
+    /// use quote::quote;
+    ///
+    /// const PATH_FIELD: Def =
+    ///     Def::new("route", "path", false, DefaultValue::Str(None)).with_position(0);
+    /// const METHOD_FIELD: Def =
+    ///     Def::new("route", "method", false, DefaultValue::Str(None)).with_position(1);
+    ///
+    /// let mut leading = true;
+    /// let path = PATH_FIELD.to_nested_in_order(&Some("/".to_owned()), true, &mut leading);
+    /// let method = METHOD_FIELD.to_nested_in_order(&Some("GET".to_owned()), true, &mut leading);
+    /// assert_eq!(quote!(#path, #method).to_string(), r#""/" , "GET""#);
+    ///
+    /// let mut leading = true;
+    /// assert!(PATH_FIELD.to_nested_in_order(&None::<String>, true, &mut leading).is_none());
+    /// let method = METHOD_FIELD.to_nested_in_order(&Some("GET".to_owned()), true, &mut leading);
+    /// assert_eq!(quote!(#method).to_string(), r#"method = "GET""#);
+    /// ```
+    pub fn to_nested_in_order<V: ToMeta>(
+        &self,
+        value: &V,
+        omit_default: bool,
+        leading: &mut bool,
+    ) -> Option<NestedMeta> {
+        let keyed = is_key(self.name);
+        let meta = value
+            .to_meta(&self.key())
+            .filter(|meta| !(omit_default && keyed && self.is_default(meta)));
+        let meta = match meta {
+            Some(meta) => meta,
+            None => {
+                if self.position.is_some() {
+                    *leading = false;
+                }
+                return None;
+            }
+        };
+
+        match meta {
+            Meta::NameValue(mnv) if self.position.is_some() && (*leading || !keyed) => {
+                Some(NestedMeta::Value(mnv.value))
+            }
+            // flags without a key are set by their value
+            Meta::Path(path) if !keyed => Some(NestedMeta::Value(Value::Lit(
+                LitBool::new(true, path.span()).into(),
+            ))),
+            meta => {
+                *leading = false;
+                Some(NestedMeta::Meta(meta))
+            }
+        }
+    }
+
+    /// push the key of this field or the keys of the fields it flattens to `keys` unless it's hidden
+    pub(crate) fn keys(&self, keys: &mut Vec<&'a str>) {
+        if self.hidden {
+            return;
+        }
+        match self.flattened {
+            Some(defs) => defs.keys(keys),
+            None if is_key(self.name) => keys.push(self.name),
+            None => {}
+        }
+    }
+
+    /// push the lines describing this field or the fields it flattens to the help text
+    pub(crate) fn help(&self, lines: &mut Vec<String>) {
+        if self.hidden {
+            return;
+        }
+        if let Some(defs) = self.flattened {
+            defs.help_lines(lines);
+            return;
+        }
+
+        let ty = Types::from(&self.default);
+        let type_name = match (self.type_name, ty) {
+            ("", Types::Any) => "value",
+            ("", ty) => ty.describe(),
+            (type_name, _) => type_name,
+        };
+        let usage = match ty {
+            Types::Flag | Types::Bool => self.name.to_owned(),
+            _ if !is_key(self.name) => format!("<{}>", type_name),
+            _ => format!("{} = <{}>", self.name, type_name),
+        };
+
+        let mut notes = Vec::new();
+        if self.required {
+            notes.push("required".to_owned());
+        }
+        if let Ok(Some(value)) = self.default.as_value() {
+            notes.push(format!("default {}", display_value(&value)));
+        }
+        if let Some(position) = self.position {
+            notes.push(format!("position {}", position));
+        }
+
+        let mut line = format!("`{}`", usage);
+        if !notes.is_empty() {
+            line.push_str(&format!(" ({})", notes.join(", ")));
+        }
+        if !self.doc.is_empty() {
+            line.push_str(&format!(": {}", self.doc));
+        }
+        lines.push(line);
+        lines.extend(
+            self.examples
+                .iter()
+                .map(|example| format!("    eg `{}`", example)),
+        );
+    }
+
+    /// checks whether the meta has the default value, missing `bool`s are false
+    fn is_default(&self, meta: &Meta) -> bool {
+        let default = match (&self.default, self.default.as_value()) {
+            (_, Ok(Some(value))) => value,
+            (DefaultValue::Bool(None), Ok(None)) => Value::Lit(parse_quote!(false)),
+            _ => return false,
+        };
+        match meta {
+            Meta::Path(_) => same_value(&default, &Value::Lit(parse_quote!(true))),
+            Meta::NameValue(mnv) => same_value(&default, &mnv.value),
+            Meta::List(_) => false,
         }
+    }
 
-        // construct a default meta
-        if let Some(lit) = self.default.as_lit() {
-            let name = format_ident!("{}", self.path);
+    /// check that the value in `meta` has the type of the field
+    ///
+    /// The values of flattened fields are checked by their own fields.
+    pub(crate) fn check_value(&self, meta: Option<Meta>) -> Result<()> {
+        fn check<V: FromMeta>(meta: Option<Meta>) -> Result<()> {
+            V::from(meta).map(drop)
+        }
 
-            return Ok(Some(parse_quote!(#name = #lit)));
+        if let Some(defs) = self.flattened {
+            return match meta {
+                Some(Meta::List(list)) => defs.check_values_nested(&list),
+                _ => Ok(()),
+            };
+        }
+        if meta.is_none() {
+            return Ok(());
         }
 
-        Ok(None)
+        match Types::from(&self.default) {
+            Types::Any => Ok(()),
+            Types::Flag => check::<Flag>(meta),
+            Types::Str => check::<String>(meta),
+            Types::ByteStr => check::<Vec<u8>>(meta),
+            Types::Byte => check::<u8>(meta),
+            Types::Char => check::<char>(meta),
+            Types::I8 => check::<i8>(meta),
+            Types::I16 => check::<i16>(meta),
+            Types::I32 => check::<i32>(meta),
+            Types::I64 => check::<i64>(meta),
+            Types::I128 => check::<i128>(meta),
+            Types::Isize => check::<isize>(meta),
+            Types::U16 => check::<u16>(meta),
+            Types::U32 => check::<u32>(meta),
+            Types::U64 => check::<u64>(meta),
+            Types::U128 => check::<u128>(meta),
+            Types::Usize => check::<usize>(meta),
+            Types::F32 => check::<f32>(meta),
+            Types::F64 => check::<f64>(meta),
+            Types::Bool => check::<bool>(meta),
+        }
     }
 
     /// try to find the literal that has the value for this field
@@ -155,21 +881,25 @@ impl<'a> Def<'a> {
     /// may return the error if the field is required but not found
     pub fn get_lit(&self, attrs: &[Attribute]) -> Result<Option<Lit>> {
         Ok(self.get_meta(attrs)?.and_then(|m| match m {
-            Meta::NameValue(nvm) => Some(nvm.lit),
+            Meta::NameValue(MetaNameValue {
+                value: Value::Lit(lit),
+                ..
+            }) => Some(lit),
             _ => None,
         }))
     }
 
-    /// try to parse the literal that has the value for this field
+    /// try to parse the value for this field
     ///
     /// # Errors
     /// may return the error if parsing fails
     pub fn get<L: Parse>(&self, attrs: &[Attribute]) -> Result<Option<L>> {
-        self.get_lit(attrs)?
-            .map(|lit| {
-                let tokens = lit.to_token_stream();
-                parse2(tokens)
+        self.get_meta(attrs)?
+            .and_then(|m| match m {
+                Meta::NameValue(mnv) => Some(mnv.value),
+                _ => None,
             })
+            .map(|value| parse2(value.into_token_stream()))
             .transpose()
     }
 
@@ -194,71 +924,314 @@ impl<'a> Def<'a> {
     pub fn get_value<V: FromMeta>(&self, attrs: &[Attribute]) -> Result<V> {
         self.get_meta(attrs).and_then(FromMeta::from)
     }
+
+    /// try to extract the value for this field, errors for a missing required field point at `span` unless there's an
+    /// attribute with the path of the field
+    ///
+    /// # Errors
+    /// may return an error if the field doesn't exist or has a value of the wrong type
+    pub fn get_value_spanned<V: FromMeta>(&self, attrs: &[Attribute], span: Span) -> Result<V> {
+        self.get_meta_spanned(attrs, span).and_then(FromMeta::from)
+    }
+
+    /// try to extract the value from a nested meta list
+    ///
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def, MetaList};
+    /// use syn::parse_quote;
+    ///
+    /// # fn main() -> syn::Result<()> {
+    /// let list: MetaList = parse_quote!(auth(user = "x"));
+    /// const USER_FIELD: Def = Def::new("auth", "user", true, DefaultValue::Str(None));
+    /// let value = USER_FIELD.get_nested_value::<String>(&list)?;
+    /// assert_eq!(value, "x");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// may return an error if the field doesn't exist or has a value of the wrong type
+    pub fn get_nested_value<V: FromMeta>(&self, list: &MetaList) -> Result<V> {
+        self.get_nested_meta(list).and_then(FromMeta::from)
+    }
+
+    /// extract the values from all metas for this field
+    ///
+    /// Values can be repeated eg `#[foo(bar = "a", bar = "b")]` or given as a list eg `#[foo(bar("a", "b"))]` or `#[foo(bar = ["a", "b"])]`.
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def};
+    /// use syn::{parse_quote, Attribute};
+    ///
+    /// # fn main() -> syn::Result<()> {
+    /// let attr1: Attribute = parse_quote!(#[foo(bar = "a", bar("b", "c"))]);
+    /// let attr2: Attribute = parse_quote!(#[foo(bar = "d")]);
+    /// const BAR_FIELD: Def = Def::new("foo", "bar", false, DefaultValue::Str(None));
+    /// let values = BAR_FIELD.get_all_values::<String>(&[attr1, attr2])?;
+    /// assert_eq!(values, vec!["a", "b", "c", "d"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// may return an error if the field is required but not found or a value has the wrong type
+    pub fn get_all_values<V: FromMeta>(&self, attrs: &[Attribute]) -> Result<Vec<V>> {
+        let metas = self.get_all_metas(attrs)?;
+        if metas.is_empty() {
+            return self.get_meta(attrs).and_then(|meta| values(meta));
+        }
+        collect(metas.into_iter().map(|meta| values(Some(meta))))
+            .map(|values| values.into_iter().flatten().collect())
+    }
+
+    /// extract the values from all metas for this field in a nested meta list
+    ///
+    /// # Errors
+    /// may return an error if the field is required but not found or a value has the wrong type
+    pub fn get_all_nested_values<V: FromMeta>(&self, list: &MetaList) -> Result<Vec<V>> {
+        let metas = self.get_all_nested_metas(list);
+        if metas.is_empty() {
+            return self.get_nested_meta(list).and_then(|meta| values(meta));
+        }
+        collect(metas.into_iter().map(|meta| values(Some(meta))))
+            .map(|values| values.into_iter().flatten().collect())
+    }
+}
+
+/// the values in front of the first key eg `"a"` and `3` in `#[foo("a", 3, bar = 4)]`
+pub(crate) fn leading_values(list: &MetaList) -> impl Iterator<Item = &Value> {
+    list.nested.iter().map_while(|nested| match nested {
+        NestedMeta::Value(value) => Some(value),
+        NestedMeta::Meta(_) => None,
+    })
+}
+
+/// collect the metas of a flattened field into a single list
+fn flattened_list(path: Path, metas: Vec<Meta>) -> Meta {
+    Meta::List(MetaList {
+        path,
+        paren_token: Paren::default(),
+        nested: metas.into_iter().map(NestedMeta::Meta).collect(),
+    })
+}
+
+/// checks whether the name is a key and not the index of an unnamed field eg `0`
+const fn is_key(name: &str) -> bool {
+    !name.is_empty() && !name.as_bytes()[0].is_ascii_digit()
+}
+
+/// compare two values ignoring spans and the suffixes of numbers eg `3` and `3u32` are the same
+fn same_value(a: &Value, b: &Value) -> bool {
+    display_value(a) == display_value(b)
+}
+
+/// checks whether `meta` gives the field the value `value` in its source form eg `"POST"`, flags are `true`
+pub(crate) fn has_value(meta: &Meta, value: &str) -> bool {
+    let value = match syn::parse_str::<Value>(value) {
+        Ok(value) => value,
+        Err(_) => return false,
+    };
+    match meta {
+        Meta::Path(_) => same_value(&value, &Value::Lit(parse_quote!(true))),
+        Meta::NameValue(mnv) => same_value(&value, &mnv.value),
+        Meta::List(_) => false,
+    }
+}
+
+/// display a value without the suffixes of numbers eg `3` instead of `3u32`
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::Lit(Lit::Int(v)) => v.base10_digits().to_owned(),
+        Value::Lit(Lit::Float(v)) => v
+            .base10_parse::<f64>()
+            .map_or_else(|_| v.to_string(), |v| v.to_string()),
+        value => value.to_token_stream().to_string(),
+    }
+}
+
+/// compare two strings in a const context
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// strip away the keys and leading values of `defs` from the attributes
+pub(crate) fn strip(defs: &[&Def], attrs: &mut Vec<Attribute>) {
+    let data = take(attrs);
+    attrs.extend(data.into_iter().filter_map(|mut a| {
+        if strip_from_attribute(defs, &mut a) {
+            None
+        } else {
+            Some(a)
+        }
+    }));
+}
+
+/// strip the attribute and return whether it was empty
+fn strip_from_attribute(defs: &[&Def], attr: &mut Attribute) -> bool {
+    // check the path
+    let defs = defs
+        .iter()
+        .filter(|def| def.matches_path(&attr.path))
+        .collect::<Vec<_>>();
+    if defs.is_empty() {
+        return false;
+    }
+
+    let mut meta = if let Ok(meta) = parse_meta(attr) {
+        meta
+    } else {
+        return false;
+    };
+
+    match &mut meta {
+        Meta::List(list) => {
+            let leading = leading_values(list).count();
+            let new_punctuated = list
+                .nested
+                .iter()
+                .enumerate()
+                .filter(|(i, nested)| match nested {
+                    NestedMeta::Meta(meta) => !defs.iter().any(|def| def.matches_name(meta.path())),
+                    NestedMeta::Value(_) => {
+                        *i >= leading || !defs.iter().any(|def| def.position == Some(*i))
+                    }
+                })
+                .map(|(_, nested)| nested.clone());
+            list.nested = Punctuated::from_iter(new_punctuated);
+
+            let empty = list.nested.is_empty();
+            *attr = parse_quote!(#[#list]);
+            empty
+        }
+        Meta::Path(_) => true,
+        Meta::NameValue(_) => false,
+    }
+}
+
+/// extract the values from a meta that is either a single value, a list or an array of values
+fn values<V: FromMeta>(meta: Option<Meta>) -> Result<Vec<V>> {
+    match meta {
+        Some(Meta::List(list)) => V::from_list(list),
+        Some(Meta::NameValue(MetaNameValue {
+            path,
+            eq_token,
+            value: Value::Expr(Expr::Array(array)),
+        })) => collect(array.elems.into_iter().map(|elem| {
+            V::from(Some(Meta::NameValue(MetaNameValue {
+                path: path.clone(),
+                eq_token,
+                value: elem.into(),
+            })))
+        })),
+        Some(meta) => V::from(Some(meta)).map(|value| vec![value]),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// collect all values or all errors combined
+fn collect<V>(results: impl Iterator<Item = Result<V>>) -> Result<Vec<V>> {
+    let mut partial = Partial::new();
+    let values = results.filter_map(|result| partial.ok(result)).collect();
+    partial.value = Some(values);
+    partial.into_result()
 }
 
 #[cfg(feature = "legacy")]
 impl Lint<Vec<Attribute>> for Def<'_> {
     fn lint(&self, input: &Vec<Attribute>, c: &mut Collector) {
-        let mut found = false;
+        // the keys of flattened fields are linted by the type they're parsed into
+        if self.flattened.is_some() {
+            return;
+        }
+
+        let mut first: Option<Meta> = None;
+        let repeatable = self.duplicates != Duplicates::Error;
 
         for attr in input.iter() {
-            let meta = attr.parse_meta().unwrap();
-            if meta.path().is_ident(self.path) {
-                if let Meta::List(list) = meta {
-                    for meta in list.nested.iter() {
-                        if let NestedMeta::Meta(meta) = meta {
-                            if meta.path().is_ident(self.name) {
-                                match meta {
-                                    Meta::NameValue(meta) => {
-                                        if found {
-                                            c.error(Error::new_spanned(
-                                                meta,
-                                                format!("dupplicate {} attribute", self.path),
-                                            ));
-                                        } else {
-                                            found = true;
-                                        }
-
-                                        let some_lit = Some(&meta.lit);
+            if self.matches_path(&attr.path) {
+                if let Ok(Meta::List(list)) = parse_meta(attr) {
+                    for meta in self.find_all_nested_metas(&list) {
+                        match &meta {
+                            Meta::NameValue(mnv) => {
+                                match &first {
+                                    Some(first) if !repeatable => {
+                                        c.error(self.duplicate_error(first, &meta));
+                                    }
+                                    Some(_) => {}
+                                    None => first = Some(meta.clone()),
+                                }
+
+                                let ty = self
+                                    .default
+                                    .ty(!self.required && !self.default.has_default_data());
+                                match &mnv.value {
+                                    Value::Lit(lit) => {
+                                        let some_lit = Some(lit);
                                         let mut subcontext = Context::new_by_ref(c, &some_lit);
-                                        subcontext.lint(
-                                            &self
-                                                .default
-                                                .ty(!self.required
-                                                    && !self.default.has_default_data()),
-                                        );
+                                        subcontext.lint(&ty);
                                     }
-                                    Meta::Path(_) => {
-                                        if found {
-                                            c.error(Error::new_spanned(
-                                                meta,
-                                                format!("dupplicate {} attribute", self.path),
-                                            ));
-                                        } else {
-                                            found = true;
-                                        }
-
-                                        let mut subcontext = Context::new_by_ref(c, &None);
-                                        subcontext.lint(
-                                            &self
-                                                .default
-                                                .ty(!self.required
-                                                    && !self.default.has_default_data()),
-                                        );
+                                    // only values of unknown types can be expressions
+                                    _ if matches!(ty.ty, Types::Any) => {}
+                                    value => c.error(Error::new_spanned(
+                                        value,
+                                        format!(
+                                            "expected {}, got {}",
+                                            ty.ty.describe(),
+                                            value.to_token_stream()
+                                        ),
+                                    )),
+                                }
+                            }
+                            Meta::Path(_) => {
+                                match &first {
+                                    Some(first) if !repeatable => {
+                                        c.error(self.duplicate_error(first, &meta));
                                     }
-                                    _ => {
-                                        c.error(Error::new_spanned(&meta, "unexpected meta list"));
+                                    Some(_) => {}
+                                    None => first = Some(meta.clone()),
+                                }
+
+                                let mut subcontext = Context::new_by_ref(c, &None);
+                                subcontext.lint(
+                                    &self
+                                        .default
+                                        .ty(!self.required && !self.default.has_default_data()),
+                                );
+                            }
+                            Meta::List(_) if matches!(Types::from(&self.default), Types::Any) => {
+                                match &first {
+                                    Some(first) if !repeatable => {
+                                        c.error(self.duplicate_error(first, &meta));
                                     }
+                                    Some(_) => {}
+                                    None => first = Some(meta.clone()),
                                 }
                             }
+                            Meta::List(_) => {
+                                c.error(Error::new_spanned(meta, "unexpected meta list"));
+                            }
                         }
                     }
                 }
             }
         }
 
-        if !found && self.required {
-            c.error(Error::new(
+        if first.is_none() && self.required {
+            c.error(self.missing_error(
+                input,
                 Span::call_site(),
                 format!("missing required {} attribute", self.name),
             ));
@@ -1,11 +1,11 @@
-use crate::Def;
 #[cfg(feature = "legacy")]
-use macro_compose::{Collector, Lint};
+use crate::Types;
+use crate::{field, parse_meta, Def, Meta, MetaList, NestedMeta, Partial, Value};
 #[cfg(feature = "legacy")]
+use macro_compose::{Collector, Lint};
+use proc_macro2::Span;
 use quote::ToTokens;
-use syn::Attribute;
-#[cfg(feature = "legacy")]
-use syn::{Error, Meta, NestedMeta, Path};
+use syn::{Attribute, Error, Path, Result};
 
 /// `Defs` is a collection of [`Def`]s
 /// # Example
@@ -20,22 +20,74 @@ use syn::{Error, Meta, NestedMeta, Path};
 /// ```
 pub struct Defs<'a> {
     defs: &'a [&'a Def<'a>],
+    exactly_one_of: &'a [&'a [&'a str]],
+    at_least_one_of: &'a [&'a [&'a str]],
 }
 
 impl<'a> Defs<'a> {
     /// create a new collection of [`Def`]s from a slice
     #[must_use]
     pub const fn new(defs: &'a [&'a Def<'a>]) -> Self {
-        Defs { defs }
+        Defs {
+            defs,
+            exactly_one_of: &[],
+            at_least_one_of: &[],
+        }
     }
 
     /// return an empty collection of [`Def`]s
     #[must_use]
     pub const fn empty() -> &'static Defs<'static> {
-        const EMPTY: Defs<'static> = Defs { defs: &[] };
+        const EMPTY: Defs<'static> = Defs::new(&[]);
         &EMPTY
     }
 
+    /// require exactly one field out of every group of names eg `&[&["sync", "async"]]`
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def, Defs};
+    /// use syn::{parse_quote, Attribute};
+    ///
+    /// const SYNC_FIELD: Def = Def::new("foo", "sync", false, DefaultValue::Flag);
+    /// const ASYNC_FIELD: Def = Def::new("foo", "async", false, DefaultValue::Flag);
+    /// const FOO_FIELDS: &[&Def] = &[&SYNC_FIELD, &ASYNC_FIELD];
+    /// const FOO_FIELD_DEFS: Defs = Defs::new(FOO_FIELDS).with_exactly_one_of(&[&["sync", "async"]]);
+    ///
+    /// let attr: Attribute = parse_quote!(#[foo(async)]);
+    /// assert!(FOO_FIELD_DEFS.check_constraints(&[attr]).is_ok());
+    /// let attr: Attribute = parse_quote!(#[foo()]);
+    /// let err = FOO_FIELD_DEFS.check_constraints(&[attr]).unwrap_err();
+    /// assert_eq!(err.to_string(), "expected exactly one of: sync, async");
+    /// ```
+    #[must_use]
+    pub const fn with_exactly_one_of(mut self, groups: &'a [&'a [&'a str]]) -> Self {
+        self.exactly_one_of = groups;
+        self
+    }
+
+    /// require at least one field out of every group of names eg `&[&["user", "token"]]`
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def, Defs};
+    /// use syn::{parse_quote, Attribute};
+    ///
+    /// const USER_FIELD: Def = Def::new("auth", "user", false, DefaultValue::Str(None));
+    /// const TOKEN_FIELD: Def = Def::new("auth", "token", false, DefaultValue::Str(None));
+    /// const AUTH_FIELDS: &[&Def] = &[&USER_FIELD, &TOKEN_FIELD];
+    /// const AUTH_FIELD_DEFS: Defs = Defs::new(AUTH_FIELDS).with_at_least_one_of(&[&["user", "token"]]);
+    ///
+    /// let attr: Attribute = parse_quote!(#[auth(user = "x", token = "y")]);
+    /// assert!(AUTH_FIELD_DEFS.check_constraints(&[attr]).is_ok());
+    /// let attr: Attribute = parse_quote!(#[auth()]);
+    /// let err = AUTH_FIELD_DEFS.check_constraints(&[attr]).unwrap_err();
+    /// assert_eq!(err.to_string(), "expected at least one of: user, token");
+    /// ```
+    #[must_use]
+    pub const fn with_at_least_one_of(mut self, groups: &'a [&'a [&'a str]]) -> Self {
+        self.at_least_one_of = groups;
+        self
+    }
+
     /// strip away the attributes for all fields
     ///
     /// This is useful for attribute macros because rust has no way of knowing which attributes were used.
@@ -71,15 +123,486 @@ impl<'a> Defs<'a> {
     /// assert_eq!(attrs, vec![attr1, attr2, attr3]);
     /// ```
     pub fn strip(&self, attrs: &mut Vec<Attribute>) {
-        for def in self.defs {
-            def.strip(attrs);
+        field::strip(self.defs, attrs);
+    }
+
+    /// find the one field out of all fields that is present
+    ///
+    /// This is useful for enums where each field is a variant.
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def, Defs};
+    /// use syn::{parse_quote, Attribute};
+    ///
+    /// # fn main() -> syn::Result<()> {
+    /// const GET_VARIANT: Def = Def::new("route", "get", false, DefaultValue::Any(None));
+    /// const POST_VARIANT: Def = Def::new("route", "post", false, DefaultValue::Any(None));
+    /// const ROUTE_VARIANTS: &[&Def] = &[&GET_VARIANT, &POST_VARIANT];
+    /// const ROUTE_VARIANT_DEFS: Defs = Defs::new(ROUTE_VARIANTS);
+    ///
+    /// let attr: Attribute = parse_quote!(#[route(post(path = "/"))]);
+    /// let (def, _) = ROUTE_VARIANT_DEFS.get_one_of(&[attr])?;
+    /// assert_eq!(def.name, "post");
+    ///
+    /// let attr: Attribute = parse_quote!(#[route(get(path = "/"), post(path = "/"))]);
+    /// assert!(ROUTE_VARIANT_DEFS.get_one_of(&[attr]).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// returns an error if none or more than one of the fields are present
+    pub fn get_one_of(&self, attrs: &[Attribute]) -> Result<(&'a Def<'a>, Meta)> {
+        self.get_one_of_spanned(attrs, Span::call_site())
+    }
+
+    /// find the one field out of all fields that is present, the error if none are present points at `span` eg the
+    /// ident of the item unless there's an attribute with the path of the fields
+    ///
+    /// # Errors
+    /// returns an error if none or more than one of the fields are present
+    pub fn get_one_of_spanned(
+        &self,
+        attrs: &[Attribute],
+        span: Span,
+    ) -> Result<(&'a Def<'a>, Meta)> {
+        let mut found = Vec::new();
+        for def in self.defs.iter() {
+            if let Some(meta) = def.find_meta(attrs)? {
+                found.push((*def, meta));
+            }
         }
+        self.one_of(found, || {
+            match attrs.iter().find(|attr| self.has_path(&attr.path)) {
+                Some(attr) => Error::new_spanned(attr, self.expected_one_of()),
+                None => Error::new(span, self.expected_one_of()),
+            }
+        })
     }
 
-    #[cfg(feature = "legacy")]
+    /// find the one field out of all fields that is present in a nested meta list
+    ///
+    /// # Errors
+    /// returns an error if none or more than one of the fields are present
+    pub fn get_one_of_nested(&self, list: &MetaList) -> Result<(&'a Def<'a>, Meta)> {
+        let found = self
+            .defs
+            .iter()
+            .filter_map(|def| {
+                def.find_nested_meta(list)
+                    .transpose()
+                    .map(|meta| meta.map(|meta| (*def, meta)))
+            })
+            .collect::<Result<_>>()?;
+        self.one_of(found, || Error::new_spanned(list, self.expected_one_of()))
+    }
+
+    fn one_of(
+        &self,
+        found: Vec<(&'a Def<'a>, Meta)>,
+        missing: impl FnOnce() -> Error,
+    ) -> Result<(&'a Def<'a>, Meta)> {
+        let mut found = found.into_iter();
+        let first = found.next().ok_or_else(missing)?;
+
+        let mut error: Option<Error> = None;
+        for (_, meta) in found {
+            let e = Error::new_spanned(
+                meta.path(),
+                format!("only one of {} may be specified", self.names()),
+            );
+            match &mut error {
+                Some(error) => error.combine(e),
+                None => error = Some(e),
+            }
+        }
+
+        error.map_or(Ok(first), Err)
+    }
+
+    fn expected_one_of(&self) -> String {
+        format!("expected one of: {}", self.names())
+    }
+
+    fn names(&self) -> String {
+        self.defs
+            .iter()
+            .map(|def| def.name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// check the attributes for keys that don't belong to any field
+    ///
+    /// Only attributes with the path of one of the fields are checked eg `foo` in `#[foo(bar = 3)]`.
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def, Defs};
+    /// use syn::{parse_quote, Attribute};
+    ///
+    /// const USER_FIELD: Def = Def::new("auth", "user", true, DefaultValue::Str(None));
+    /// const AUTH_FIELDS: &[&Def] = &[&USER_FIELD];
+    /// const AUTH_FIELD_DEFS: Defs = Defs::new(AUTH_FIELDS);
+    ///
+    /// let attr1: Attribute = parse_quote!(#[auth(user = "x")]);
+    /// let attr2: Attribute = parse_quote!(#[other(pasword = "y")]);
+    /// assert!(AUTH_FIELD_DEFS.check_unknown(&[attr1, attr2]).is_ok());
+    /// let attr: Attribute = parse_quote!(#[auth(user = "x", pasword = "y")]);
+    /// assert!(AUTH_FIELD_DEFS.check_unknown(&[attr]).is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// returns an error for every unrecognized key
+    pub fn check_unknown(&self, attrs: &[Attribute]) -> Result<()> {
+        let mut error: Option<Error> = None;
+        for attr in attrs.iter().filter(|attr| self.has_path(&attr.path)) {
+            let res = parse_meta(attr).and_then(|meta| match meta {
+                Meta::List(list) => self.check_unknown_nested(&list),
+                _ => Ok(()),
+            });
+            if let Err(e) = res {
+                match &mut error {
+                    Some(error) => error.combine(e),
+                    None => error = Some(e),
+                }
+            }
+        }
+
+        error.map_or(Ok(()), Err)
+    }
+
+    /// check a nested meta list for keys that don't belong to any field
+    ///
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def, Defs, MetaList};
+    /// use syn::parse_quote;
+    ///
+    /// const USER_FIELD: Def = Def::new("auth", "user", true, DefaultValue::Str(None));
+    /// const AUTH_FIELDS: &[&Def] = &[&USER_FIELD];
+    /// const AUTH_FIELD_DEFS: Defs = Defs::new(AUTH_FIELDS);
+    ///
+    /// let list: MetaList = parse_quote!(auth(user = "x"));
+    /// assert!(AUTH_FIELD_DEFS.check_unknown_nested(&list).is_ok());
+    /// let list: MetaList = parse_quote!(auth(user = "x", pasword = "y"));
+    /// assert!(AUTH_FIELD_DEFS.check_unknown_nested(&list).is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// returns an error for every unrecognized key
+    pub fn check_unknown_nested(&self, list: &MetaList) -> Result<()> {
+        let mut error: Option<Error> = None;
+        let mut push = |e: Error| match &mut error {
+            Some(error) => error.combine(e),
+            None => error = Some(e),
+        };
+
+        for (i, meta) in list.nested.iter().enumerate() {
+            match meta {
+                NestedMeta::Meta(meta) => {
+                    let is_part_of_defs = |def: &&Def| def.matches_name(meta.path());
+                    if !self.defs.iter().any(is_part_of_defs) {
+                        push(Error::new_spanned(
+                            meta.path(),
+                            self.unrecognized(&list.path, meta.path()),
+                        ));
+                    }
+                }
+                NestedMeta::Value(v) => {
+                    if let Err(e) = self.check_positional(list, i, v) {
+                        push(e);
+                    }
+                }
+            }
+        }
+
+        error.map_or(Ok(()), Err)
+    }
+
+    /// check the attributes for all fields and collect every error instead of stopping at the first one
+    ///
+    /// Missing required fields, values of the wrong type, fields that are given more than once, unknown keys and
+    /// violated constraints between fields are reported together.
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def, Defs};
+    /// use syn::{parse_quote, Attribute};
+    ///
+    /// const USER_FIELD: Def = Def::new("auth", "user", true, DefaultValue::Str(None));
+    /// const RETRIES_FIELD: Def = Def::new("auth", "retries", false, DefaultValue::U16(None));
+    /// const AUTH_FIELDS: &[&Def] = &[&USER_FIELD, &RETRIES_FIELD];
+    /// const AUTH_FIELD_DEFS: Defs = Defs::new(AUTH_FIELDS);
+    ///
+    /// let attr: Attribute = parse_quote!(#[auth(user = "x", retries = 3)]);
+    /// assert!(AUTH_FIELD_DEFS.check(&[attr]).is_ok());
+    ///
+    /// let attr: Attribute = parse_quote!(#[auth(retries = "3", pasword = "y")]);
+    /// let err = AUTH_FIELD_DEFS.check(&[attr]).unwrap_err();
+    /// assert_eq!(err.into_iter().count(), 3);
+    /// ```
+    ///
+    /// # Errors
+    /// returns all errors combined
+    pub fn check(&self, attrs: &[Attribute]) -> Result<()> {
+        let mut partial = Partial::new();
+        partial.ok(self.check_unknown(attrs));
+        for def in self.defs.iter() {
+            partial.ok(def.get_meta(attrs).and_then(|meta| def.check_value(meta)));
+        }
+        partial.ok(self.check_constraints(attrs));
+        partial.value = Some(());
+        partial.into_result()
+    }
+
+    /// check a nested meta list for all fields and collect every error instead of stopping at the first one
+    ///
+    /// # Errors
+    /// returns all errors combined
+    pub fn check_nested(&self, list: &MetaList) -> Result<()> {
+        let mut partial = Partial::new();
+        partial.ok(self.check_unknown_nested(list));
+        partial.ok(self.check_values_nested(list));
+        partial.ok(self.check_constraints_nested(list));
+        partial.value = Some(());
+        partial.into_result()
+    }
+
+    /// check the values of all fields in a nested meta list without checking for unknown keys
+    pub(crate) fn check_values_nested(&self, list: &MetaList) -> Result<()> {
+        let mut partial = Partial::new();
+        for def in self.defs.iter() {
+            partial.ok(def
+                .get_nested_meta(list)
+                .and_then(|meta| def.check_value(meta)));
+        }
+        partial.value = Some(());
+        partial.into_result()
+    }
+
+    /// check the constraints between fields and collect every error
+    ///
+    /// The constraints are the conflicts, requirements and conditional requirements of the fields and the groups of
+    /// [`with_exactly_one_of`](Self::with_exactly_one_of) and [`with_at_least_one_of`](Self::with_at_least_one_of).
+    /// Conditional requirements on fields that aren't given are checked against their default value.
+    /// Flattened fields are given if at least one of their keys is.
+    /// Errors point at each involved key, errors for missing fields at the attribute with the path of the fields.
+    ///
+    /// # Errors
+    /// returns all violated constraints combined
+    pub fn check_constraints(&self, attrs: &[Attribute]) -> Result<()> {
+        let given = self
+            .defs
+            .iter()
+            .map(|def| given(def, def.find_meta(attrs).ok().flatten()))
+            .collect::<Vec<_>>();
+        self.constraints(&given, |message| {
+            match attrs.iter().find(|attr| self.has_path(&attr.path)) {
+                Some(attr) => Error::new_spanned(attr, message),
+                None => Error::new(Span::call_site(), message),
+            }
+        })
+    }
+
+    /// check the constraints between fields in a nested meta list and collect every error
+    ///
+    /// # Errors
+    /// returns all violated constraints combined
+    pub fn check_constraints_nested(&self, list: &MetaList) -> Result<()> {
+        let given = self
+            .defs
+            .iter()
+            .map(|def| given(def, def.find_nested_meta(list).ok().flatten()))
+            .collect::<Vec<_>>();
+        self.constraints(&given, |message| Error::new_spanned(list, message))
+    }
+
+    /// check the constraints given the meta of every field, `missing` creates the errors for missing fields
+    fn constraints(&self, given: &[Option<Meta>], missing: impl Fn(String) -> Error) -> Result<()> {
+        let find = |name: &str| {
+            self.defs
+                .iter()
+                .position(|def| def.name == name)
+                .and_then(|i| given[i].as_ref().map(|meta| (self.defs[i], meta)))
+        };
+
+        let mut partial = Partial::new();
+        for (i, (def, meta)) in self.defs.iter().zip(given).enumerate() {
+            let meta = match meta {
+                Some(meta) => meta,
+                None => {
+                    for (name, value) in def.required_if.iter() {
+                        let message = format!("{} is required if {} = {}", def.name, name, value);
+                        match find(name) {
+                            Some((_, other)) => {
+                                if field::has_value(other, value) {
+                                    partial.push_error(Error::new_spanned(other, message));
+                                }
+                            }
+                            // fields that aren't given have their default value
+                            None => {
+                                let default = self
+                                    .defs
+                                    .iter()
+                                    .find(|other| other.name == *name)
+                                    .and_then(|other| other.default_meta().ok().flatten());
+                                if default
+                                    .map_or(false, |default| field::has_value(&default, value))
+                                {
+                                    partial.push_error(missing(message));
+                                }
+                            }
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            for name in def.conflicts_with.iter() {
+                if let Some((other_def, other)) = find(name) {
+                    // conflicts declared on both fields are only reported once
+                    let position = self.defs.iter().position(|d| d.name == other_def.name);
+                    if other_def.conflicts_with.contains(&def.name) && position < Some(i) {
+                        continue;
+                    }
+
+                    let message = format!("{} can't be combined with {}", def.name, name);
+                    partial.push_error(Error::new_spanned(meta.path(), &message));
+                    partial.push_error(Error::new_spanned(other.path(), message));
+                }
+            }
+
+            for name in def.requires.iter() {
+                if find(name).is_none() {
+                    partial.push_error(Error::new_spanned(
+                        meta.path(),
+                        format!("{} requires {}", def.name, name),
+                    ));
+                }
+            }
+        }
+
+        for group in self.exactly_one_of.iter() {
+            let found = group
+                .iter()
+                .filter_map(|name| find(name))
+                .collect::<Vec<_>>();
+            let names = group.join(", ");
+            match found.as_slice() {
+                [] => partial.push_error(missing(format!("expected exactly one of: {}", names))),
+                [_] => {}
+                found => {
+                    for (_, meta) in found {
+                        partial.push_error(Error::new_spanned(
+                            meta.path(),
+                            format!("only one of {} may be specified", names),
+                        ));
+                    }
+                }
+            }
+        }
+        for group in self.at_least_one_of.iter() {
+            if group.iter().all(|name| find(name).is_none()) {
+                partial.push_error(missing(format!(
+                    "expected at least one of: {}",
+                    group.join(", ")
+                )));
+            }
+        }
+
+        partial.value = Some(());
+        partial.into_result()
+    }
+
+    /// check that the value at `index` is a leading value that belongs to a field
+    fn check_positional(&self, list: &MetaList, index: usize, value: &Value) -> Result<()> {
+        if index >= field::leading_values(list).count() {
+            return Err(Error::new_spanned(value, "expected meta"));
+        }
+
+        if self.defs.iter().any(|def| def.position == Some(index)) {
+            Ok(())
+        } else {
+            Err(Error::new_spanned(value, "unexpected positional argument"))
+        }
+    }
+
+    /// a reference of all fields that aren't hidden, one field per line
+    ///
+    /// Every line has the usage of the field, its default value and whether it's required followed by its description
+    /// eg ``"`timeout = <i32>` (default 3): request timeout"``.
+    /// Examples are on separate lines after their field.
+    /// ```
+    /// # use macro_input_core as macro_input;
+    /// use macro_input::{DefaultValue, Def, Defs};
+    ///
+    /// const PATH_FIELD: Def = Def::new("route", "path", true, DefaultValue::Str(None))
+    ///     .with_type_name("String")
+    ///     .with_doc("the path of the route");
+    /// const CACHE_FIELD: Def = Def::new("route", "cache", false, DefaultValue::Flag);
+    /// const ROUTE_FIELDS: &[&Def] = &[&PATH_FIELD, &CACHE_FIELD];
+    /// const ROUTE_FIELD_DEFS: Defs = Defs::new(ROUTE_FIELDS);
+    ///
+    /// assert_eq!(
+    ///     ROUTE_FIELD_DEFS.help(),
+    ///     "`path = <String>` (required): the path of the route\n`cache`"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn help(&self) -> String {
+        let mut lines = Vec::new();
+        self.help_lines(&mut lines);
+        lines.join("\n")
+    }
+
+    pub(crate) fn help_lines(&self, lines: &mut Vec<String>) {
+        for def in self.defs.iter() {
+            def.help(lines);
+        }
+    }
+
+    /// push the keys of all fields that aren't hidden
+    pub(crate) fn keys(&self, keys: &mut Vec<&'a str>) {
+        for def in self.defs.iter() {
+            def.keys(keys);
+        }
+    }
+
+    /// the error message for a key that doesn't belong to any field followed by the accepted keys
+    fn unrecognized(&self, path: &Path, key: &Path) -> String {
+        let mut message = format!(
+            "unrecognized attribute: {}::{}",
+            path.to_token_stream(),
+            key.to_token_stream()
+        );
+        let mut keys = Vec::new();
+        self.keys(&mut keys);
+        if !keys.is_empty() {
+            message += &format!(", expected one of: {}", keys.join(", "));
+        }
+        message
+    }
+
+    /// checks whether any of the fields accepts a common key with `def`
+    pub(crate) const fn shares_key(&self, def: &Def) -> bool {
+        let mut i = 0;
+        while i < self.defs.len() {
+            if self.defs[i].shares_key(def) {
+                return true;
+            }
+            i += 1;
+        }
+        false
+    }
+
+    /// checks whether the path of a meta is the name of any of the fields
+    pub(crate) fn matches_name(&self, path: &Path) -> bool {
+        self.defs.iter().any(|def| def.matches_name(path))
+    }
+
+    /// checks whether the path of an attribute is the path of any of the fields
     fn has_path(&self, path: &Path) -> bool {
         for def in self.defs.iter() {
-            if path.is_ident(def.path) {
+            if def.matches_path(path) {
                 return true;
             }
         }
@@ -94,6 +617,14 @@ impl<'a> From<&'a [&'a Def<'a>]> for Defs<'a> {
     }
 }
 
+/// the meta of a field if it's given, flattened fields are only given if at least one of their keys is
+fn given(def: &Def, meta: Option<Meta>) -> Option<Meta> {
+    match meta {
+        Some(Meta::List(list)) if def.flattened.is_some() && list.nested.is_empty() => None,
+        meta => meta,
+    }
+}
+
 #[cfg(feature = "legacy")]
 impl Lint<Vec<Attribute>> for Defs<'_> {
     fn lint(&self, input: &Vec<Attribute>, c: &mut Collector) {
@@ -102,16 +633,30 @@ impl Lint<Vec<Attribute>> for Defs<'_> {
         }
 
         for attr in input.iter() {
-            let meta = attr.parse_meta().unwrap();
-            let path = meta.path();
-            if self.has_path(path) {
+            if self.has_path(&attr.path) {
+                let meta = match parse_meta(attr) {
+                    Ok(meta) => meta,
+                    Err(e) => {
+                        c.error(e);
+                        continue;
+                    }
+                };
+                let path = meta.path();
                 match &meta {
                     Meta::List(list) => {
-                        for meta in list.nested.iter() {
+                        for (i, meta) in list.nested.iter().enumerate() {
                             match meta {
                                 NestedMeta::Meta(meta) => {
-                                    match meta {
-                                        Meta::NameValue(_) | Meta::Path(_) => {}
+                                    let is_part_of_defs = |def: &&&Def| {
+                                        def.matches_path(path) && def.matches_name(meta.path())
+                                    };
+                                    let def = self.defs.iter().find(is_part_of_defs);
+
+                                    match (meta, def) {
+                                        (Meta::NameValue(_), _) | (Meta::Path(_), _) => {}
+                                        // lists are only valid for values that aren't literals
+                                        (Meta::List(_), Some(def))
+                                            if matches!(Types::from(&def.default), Types::Any) => {}
                                         _ => {
                                             c.error(Error::new_spanned(
                                                 meta,
@@ -120,22 +665,17 @@ impl Lint<Vec<Attribute>> for Defs<'_> {
                                         }
                                     }
 
-                                    let is_part_of_defs = |def: &&Def| {
-                                        path.is_ident(&def.path) && meta.path().is_ident(&def.name)
-                                    };
-                                    if !self.defs.iter().any(is_part_of_defs) {
+                                    if def.is_none() {
                                         c.error(Error::new_spanned(
                                             meta,
-                                            format!(
-                                                "unrecognized attribute: {}::{}",
-                                                path.to_token_stream(),
-                                                meta.path().to_token_stream()
-                                            ),
+                                            self.unrecognized(path, meta.path()),
                                         ));
                                     }
                                 }
-                                NestedMeta::Lit(l) => {
-                                    c.error(Error::new_spanned(l, "expected meta"));
+                                NestedMeta::Value(v) => {
+                                    if let Err(e) = self.check_positional(list, i, v) {
+                                        c.error(e);
+                                    }
                                 }
                             }
                         }
@@ -0,0 +1,90 @@
+use crate::{FromMeta, Meta, ToMeta};
+use proc_macro2::Span;
+use syn::{spanned::Spanned, Error, Path, Result};
+
+/// a flag that doesn't have a value eg `#[my_input(enabled)]`
+///
+/// Unlike `Option<()>` a `Flag` remembers where it was set.
+/// # Example
+/// ```
+/// # use macro_input_core as macro_input;
+/// use macro_input::{DefaultValue, Def, Flag};
+/// use syn::{parse_quote, Attribute};
+///
+/// # fn main() -> syn::Result<()> {
+/// const ENABLED_FIELD: Def = Def::new("foo", "enabled", false, DefaultValue::Flag);
+///
+/// let attr: Attribute = parse_quote!(#[foo(enabled)]);
+/// let enabled = ENABLED_FIELD.get_value::<Flag>(&[attr])?;
+/// assert!(enabled.is_set());
+/// assert!(enabled.span().is_some());
+///
+/// let attr: Attribute = parse_quote!(#[foo()]);
+/// let enabled = ENABLED_FIELD.get_value::<Flag>(&[attr])?;
+/// assert!(!enabled.is_set());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Flag {
+    span: Option<Span>,
+}
+
+impl Flag {
+    /// create a flag that is set
+    #[must_use]
+    pub const fn set(span: Span) -> Self {
+        Flag { span: Some(span) }
+    }
+
+    /// create a flag that isn't set
+    #[must_use]
+    pub const fn unset() -> Self {
+        Flag { span: None }
+    }
+
+    /// checks whether the flag is set
+    #[must_use]
+    pub const fn is_set(&self) -> bool {
+        self.span.is_some()
+    }
+
+    /// the span of the flag if it is set
+    #[must_use]
+    pub const fn span(&self) -> Option<Span> {
+        self.span
+    }
+}
+
+/// only whether the flags are set is compared, spans are ignored
+impl PartialEq for Flag {
+    fn eq(&self, other: &Self) -> bool {
+        self.is_set() == other.is_set()
+    }
+}
+
+impl Eq for Flag {}
+
+impl From<Flag> for bool {
+    fn from(flag: Flag) -> Self {
+        flag.is_set()
+    }
+}
+
+impl FromMeta for Flag {
+    fn from(meta: Option<Meta>) -> Result<Self> {
+        meta.map_or(Ok(Flag::unset()), |m| {
+            if matches!(m, Meta::Path(_)) {
+                Ok(Flag::set(m.span()))
+            } else {
+                Err(Error::new_spanned(m, "unexpected value"))
+            }
+        })
+    }
+}
+
+impl ToMeta for Flag {
+    fn to_meta(&self, path: &Path) -> Option<Meta> {
+        self.span.map(|_| Meta::Path(path.clone()))
+    }
+}
@@ -1,18 +1,29 @@
-//!
+//! the core part of macro-input
 #![deny(missing_docs, clippy::doc_markdown)]
 
 mod convert;
+mod duplicates;
 mod field;
 mod fields;
+mod flag;
+mod meta;
+mod partial;
+mod spanned;
 #[cfg(feature = "legacy")]
 mod r#struct;
 mod ty;
+pub mod validate;
 mod value;
 
 pub use convert::*;
+pub use duplicates::Duplicates;
 pub use field::Def;
 pub use fields::Defs;
+pub use flag::Flag;
+pub use meta::{parse_meta, Meta, MetaList, MetaNameValue, NestedMeta, Value};
+pub use partial::Partial;
 #[cfg(feature = "legacy")]
 pub use r#struct::StructLint;
+pub use spanned::Spanned;
 pub use ty::{Type, Types};
 pub use value::DefaultValue;
@@ -0,0 +1,328 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{
+    ext::IdentExt,
+    parenthesized,
+    parse::{discouraged::Speculative, Parse, ParseStream, Parser},
+    parse2,
+    punctuated::Punctuated,
+    token::{Bracket, Comma, Paren},
+    AttrStyle, Attribute, Expr, Ident, Lit, Path, PathSegment, Result, Token,
+};
+
+/// an attribute argument eg `bar`, `bar = 3` or `bar(baz = 3)` in `#[foo(bar, bar = 3, bar(baz = 3))]`
+///
+/// Unlike `syn::Meta` the value of a name-value pair can be any expression or token tree.
+#[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum Meta {
+    /// a path without a value eg `bar` in `#[foo(bar)]`
+    Path(Path),
+    /// a list of nested arguments eg `bar(baz = 3)` in `#[foo(bar(baz = 3))]`
+    List(MetaList),
+    /// a name with a value eg `bar = 3` in `#[foo(bar = 3)]`
+    NameValue(MetaNameValue),
+}
+
+impl Meta {
+    /// the path of the argument eg `bar` in `bar = 3`
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        match self {
+            Meta::Path(path) => path,
+            Meta::List(list) => &list.path,
+            Meta::NameValue(mnv) => &mnv.path,
+        }
+    }
+}
+
+/// a list of nested arguments eg `bar(baz = 3, qux)`
+#[derive(Clone, Debug)]
+pub struct MetaList {
+    /// the path in front of the list eg `bar`
+    pub path: Path,
+    /// the parentheses around the nested arguments
+    pub paren_token: Paren,
+    /// the nested arguments eg `baz = 3` and `qux`
+    pub nested: Punctuated<NestedMeta, Comma>,
+}
+
+impl MetaList {
+    /// turn the list into an outer attribute eg `#[bar(baz = 3, qux)]`
+    #[must_use]
+    pub fn into_attribute(self) -> Attribute {
+        let mut tokens = TokenStream::new();
+        self.paren_token.surround(&mut tokens, |tokens| {
+            self.nested.to_tokens(tokens);
+        });
+        Attribute {
+            pound_token: Token![#](self.paren_token.span),
+            style: AttrStyle::Outer,
+            bracket_token: Bracket(self.paren_token.span),
+            path: self.path,
+            tokens,
+        }
+    }
+}
+
+/// a name with a value eg `bar = 3`
+#[derive(Clone, Debug)]
+pub struct MetaNameValue {
+    /// the name eg `bar`
+    pub path: Path,
+    /// the `=`
+    pub eq_token: Token![=],
+    /// the value eg `3`
+    pub value: Value,
+}
+
+/// an element of a [`MetaList`]
+#[derive(Clone, Debug)]
+pub enum NestedMeta {
+    /// a nested argument eg `baz = 3` in `bar(baz = 3)`
+    Meta(Meta),
+    /// a value eg `3` or `-3` in `bar(3, -3)`
+    Value(Value),
+}
+
+impl NestedMeta {
+    /// interpret the element as a value eg `Clone` in `derive(Clone)`
+    #[must_use]
+    pub fn into_value(self) -> Value {
+        match self {
+            NestedMeta::Value(value) => value,
+            NestedMeta::Meta(Meta::NameValue(mnv)) => mnv.value,
+            NestedMeta::Meta(meta) => Value::from_tokens(meta.into_token_stream()),
+        }
+    }
+}
+
+/// the value of an argument
+///
+/// Values end at the next comma that isn't nested in a group,
+/// so a value containing a comma eg `HashMap<K, V>` has to be wrapped in parentheses or a string.
+#[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum Value {
+    /// a literal eg `3`, `-3` or `"foo"`
+    Lit(Lit),
+    /// any other expression eg `Json`, `some::CONST`, `[1, 2, 3]` or `(1, 2)`
+    Expr(Expr),
+    /// tokens that don't form an expression eg `Vec<u8>`
+    Verbatim(TokenStream),
+}
+
+impl Value {
+    /// classify tokens as a literal, an expression or verbatim tokens
+    fn from_tokens(tokens: TokenStream) -> Self {
+        if let Ok(lit) = parse2(tokens.clone()) {
+            Value::Lit(lit)
+        } else if let Ok(expr) = parse2(tokens.clone()) {
+            Value::Expr(expr)
+        } else {
+            Value::Verbatim(tokens)
+        }
+    }
+}
+
+impl From<Lit> for Value {
+    fn from(lit: Lit) -> Self {
+        Value::Lit(lit)
+    }
+}
+
+impl From<Expr> for Value {
+    fn from(expr: Expr) -> Self {
+        match expr {
+            Expr::Lit(lit) if lit.attrs.is_empty() => Value::Lit(lit.lit),
+            expr => Value::from_tokens(expr.into_token_stream()),
+        }
+    }
+}
+
+/// parse an attribute into a [`Meta`]
+///
+/// This replaces `Attribute::parse_meta` and also accepts values that aren't literals.
+/// ```
+/// # use macro_input_core as macro_input;
+/// use macro_input::{parse_meta, Meta, NestedMeta, Value};
+/// use syn::{parse_quote, Attribute};
+///
+/// # fn main() -> syn::Result<()> {
+/// let attr: Attribute = parse_quote!(#[foo(offset = -4, kind = Json, ty = Vec<u8>)]);
+/// let list = match parse_meta(&attr)? {
+///     Meta::List(list) => list,
+///     _ => unreachable!(),
+/// };
+/// let values = list
+///     .nested
+///     .into_iter()
+///     .map(NestedMeta::into_value)
+///     .collect::<Vec<_>>();
+/// assert!(matches!(values[0], Value::Lit(_)));
+/// assert!(matches!(values[1], Value::Expr(_)));
+/// assert!(matches!(values[2], Value::Verbatim(_)));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// returns an error if the attribute isn't a path, a list or a name-value pair
+pub fn parse_meta(attr: &Attribute) -> Result<Meta> {
+    let path = attr.path.clone();
+    let parser = |input: ParseStream| parse_meta_after_path(path, input);
+    parser.parse2(attr.tokens.clone())
+}
+
+impl Parse for Meta {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.call(parse_meta_path)?;
+        parse_meta_after_path(path, input)
+    }
+}
+
+impl Parse for MetaList {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.call(parse_meta_path)?;
+        parse_meta_list_after_path(path, input)
+    }
+}
+
+impl Parse for MetaNameValue {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.call(parse_meta_path)?;
+        parse_meta_name_value_after_path(path, input)
+    }
+}
+
+impl Parse for NestedMeta {
+    fn parse(input: ParseStream) -> Result<Self> {
+        // `true` and `false` are values, not paths
+        if input.peek(Lit) {
+            return input.parse().map(NestedMeta::Value);
+        }
+
+        // a path is only a meta if it isn't the start of a longer expression eg `a + 1`
+        let fork = input.fork();
+        if let Ok(meta) = fork.parse::<Meta>() {
+            if fork.is_empty() || fork.peek(Token![,]) {
+                input.advance_to(&fork);
+                return Ok(NestedMeta::Meta(meta));
+            }
+        }
+
+        input.parse().map(NestedMeta::Value)
+    }
+}
+
+impl Parse for Value {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.is_empty() || input.peek(Token![,]) {
+            return Err(input.error("expected value"));
+        }
+
+        let mut tokens = TokenStream::new();
+        while !input.is_empty() && !input.peek(Token![,]) {
+            tokens.extend(Some(input.parse::<TokenTree>()?));
+        }
+        Ok(Value::from_tokens(tokens))
+    }
+}
+
+/// parse a path that may contain keywords eg `type` or `crate`
+fn parse_meta_path(input: ParseStream) -> Result<Path> {
+    Ok(Path {
+        leading_colon: input.parse()?,
+        segments: {
+            let mut segments = Punctuated::new();
+            while input.peek(Ident::peek_any) {
+                let ident = Ident::parse_any(input)?;
+                segments.push_value(PathSegment::from(ident));
+                if !input.peek(Token![::]) {
+                    break;
+                }
+                let punct = input.parse()?;
+                segments.push_punct(punct);
+            }
+            if segments.is_empty() {
+                return Err(input.error("expected path"));
+            } else if segments.trailing_punct() {
+                return Err(input.error("expected path segment"));
+            }
+            segments
+        },
+    })
+}
+
+fn parse_meta_after_path(path: Path, input: ParseStream) -> Result<Meta> {
+    if input.peek(Paren) {
+        parse_meta_list_after_path(path, input).map(Meta::List)
+    } else if input.peek(Token![=]) {
+        parse_meta_name_value_after_path(path, input).map(Meta::NameValue)
+    } else {
+        Ok(Meta::Path(path))
+    }
+}
+
+fn parse_meta_list_after_path(path: Path, input: ParseStream) -> Result<MetaList> {
+    let content;
+    Ok(MetaList {
+        path,
+        paren_token: parenthesized!(content in input),
+        nested: content.parse_terminated(NestedMeta::parse)?,
+    })
+}
+
+fn parse_meta_name_value_after_path(path: Path, input: ParseStream) -> Result<MetaNameValue> {
+    Ok(MetaNameValue {
+        path,
+        eq_token: input.parse()?,
+        value: input.parse()?,
+    })
+}
+
+impl ToTokens for Meta {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Meta::Path(path) => path.to_tokens(tokens),
+            Meta::List(list) => list.to_tokens(tokens),
+            Meta::NameValue(mnv) => mnv.to_tokens(tokens),
+        }
+    }
+}
+
+impl ToTokens for MetaList {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.path.to_tokens(tokens);
+        self.paren_token.surround(tokens, |tokens| {
+            self.nested.to_tokens(tokens);
+        });
+    }
+}
+
+impl ToTokens for MetaNameValue {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.path.to_tokens(tokens);
+        self.eq_token.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for NestedMeta {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            NestedMeta::Meta(meta) => meta.to_tokens(tokens),
+            NestedMeta::Value(value) => value.to_tokens(tokens),
+        }
+    }
+}
+
+impl ToTokens for Value {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Value::Lit(lit) => lit.to_tokens(tokens),
+            Value::Expr(expr) => expr.to_tokens(tokens),
+            Value::Verbatim(verbatim) => verbatim.to_tokens(tokens),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use proc_macro2::Span;
+use syn::{Error, Result};
+
+/// a possibly incomplete value together with all errors that came up while parsing it
+///
+/// Parsing doesn't stop at the first error, fields that can't be parsed fall back to their default value if they have one.
+/// This is useful for macros that should keep expanding while the input is being edited eg for code completion.
+/// # Example
+/// ```
+/// # use macro_input_core as macro_input;
+/// use macro_input::Partial;
+/// use syn::{Error, Result};
+/// use proc_macro2::Span;
+///
+/// let mut partial = Partial::new();
+/// let a = partial.ok(Ok(1));
+/// let b = partial.ok::<i32>(Err(Error::new(Span::call_site(), "expected i32")));
+/// partial.value = Some(a.unwrap_or_default() + b.unwrap_or_default());
+///
+/// assert_eq!(partial.value, Some(1));
+/// assert!(partial.into_result().is_err());
+/// ```
+#[derive(Debug)]
+pub struct Partial<T> {
+    /// the value if it could be constructed
+    pub value: Option<T>,
+    /// all errors combined
+    pub error: Option<Error>,
+}
+
+impl<T> Partial<T> {
+    /// create a partial without a value or errors
+    #[must_use]
+    pub const fn new() -> Self {
+        Partial {
+            value: None,
+            error: None,
+        }
+    }
+
+    /// add an error
+    pub fn push_error(&mut self, e: Error) {
+        match &mut self.error {
+            Some(error) => error.combine(e),
+            None => self.error = Some(e),
+        }
+    }
+
+    /// keep the error of a result if there is one and return the value otherwise
+    pub fn ok<V>(&mut self, result: Result<V>) -> Option<V> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.push_error(e);
+                None
+            }
+        }
+    }
+
+    /// convert the value with `f`, the errors are kept
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Partial<U> {
+        Partial {
+            value: self.value.map(f),
+            error: self.error,
+        }
+    }
+
+    /// the value if there were no errors
+    ///
+    /// # Errors
+    /// returns all errors combined if there were any
+    pub fn into_result(self) -> Result<T> {
+        match (self.value, self.error) {
+            (_, Some(error)) => Err(error),
+            (Some(value), None) => Ok(value),
+            (None, None) => Err(Error::new(Span::call_site(), "missing value")),
+        }
+    }
+}
+
+impl<T> Default for Partial<T> {
+    fn default() -> Self {
+        Partial::new()
+    }
+}
+
+impl<T> From<Result<T>> for Partial<T> {
+    fn from(result: Result<T>) -> Self {
+        let mut partial = Partial::new();
+        partial.value = partial.ok(result);
+        partial
+    }
+}
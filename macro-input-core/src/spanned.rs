@@ -0,0 +1,103 @@
+use crate::{FromMeta, Meta, MetaList, ToMeta};
+use proc_macro2::Span;
+use std::ops::{Deref, DerefMut};
+use syn::{spanned::Spanned as _, Path, Result};
+
+/// a value together with the span of the meta it was parsed from
+///
+/// The span can be used for reporting errors after parsing.
+/// # Example
+/// ```
+/// # use macro_input_core as macro_input;
+/// use macro_input::{DefaultValue, Def, Spanned};
+/// use syn::{parse_quote, Attribute, Error};
+///
+/// # fn main() -> syn::Result<()> {
+/// let attr: Attribute = parse_quote!(#[foo(timeout = 0)]);
+/// const TIMEOUT_FIELD: Def = Def::new("foo", "timeout", true, DefaultValue::U32(None));
+/// let timeout = TIMEOUT_FIELD.get_value::<Spanned<u32>>(&[attr])?;
+/// if *timeout == 0 {
+///     let error = Error::new(timeout.span(), "timeout must not be 0");
+///     # let _ = error;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Spanned<T> {
+    value: T,
+    span: Span,
+}
+
+impl<T> Spanned<T> {
+    /// create a new value with a span
+    #[must_use]
+    pub const fn new(value: T, span: Span) -> Self {
+        Spanned { value, span }
+    }
+
+    /// the span of the meta the value was parsed from
+    #[must_use]
+    pub const fn span(&self) -> Span {
+        self.span
+    }
+
+    /// return the value without the span
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Spanned<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+/// only the values are compared, spans are ignored
+impl<T: PartialEq> PartialEq for Spanned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for Spanned<T> {}
+
+impl<T: FromMeta> FromMeta for Spanned<T> {
+    fn from(meta: Option<Meta>) -> Result<Self> {
+        let span = meta.as_ref().map_or_else(Span::call_site, Meta::span);
+        T::from(meta).map(|value| Spanned::new(value, span))
+    }
+
+    fn from_list(list: MetaList) -> Result<Vec<Self>> {
+        T::from_list_spanned(list).map(|values| {
+            values
+                .into_iter()
+                .map(|(value, span)| Spanned::new(value, span))
+                .collect()
+        })
+    }
+
+    fn from_list_spanned(list: MetaList) -> Result<Vec<(Self, Span)>> {
+        T::from_list_spanned(list).map(|values| {
+            values
+                .into_iter()
+                .map(|(value, span)| (Spanned::new(value, span), span))
+                .collect()
+        })
+    }
+}
+
+impl<T: ToMeta> ToMeta for Spanned<T> {
+    fn to_meta(&self, path: &Path) -> Option<Meta> {
+        self.value.to_meta(path)
+    }
+}
@@ -1,8 +1,8 @@
 use crate::Defs;
 use macro_compose::{Collector, Context, Lint};
-use syn::{Data, DeriveInput, Error, ItemStruct};
+use syn::{Data, DeriveInput, Error, ItemEnum, ItemStruct};
 
-/// a lint for [`syn::ItemStruct`] or [`syn::DeriveInput`]
+/// a lint for [`syn::ItemStruct`], [`syn::ItemEnum`] or [`syn::DeriveInput`]
 /// # Example
 /// ```
 /// # use macro_input_core as macro_input;
@@ -17,6 +17,7 @@ use syn::{Data, DeriveInput, Error, ItemStruct};
 /// ```
 pub struct StructLint<'a> {
     struct_defs: &'a Defs<'a>,
+    variant_defs: Option<&'a Defs<'a>>,
     fields_defs: &'a Defs<'a>,
 }
 
@@ -28,6 +29,23 @@ impl<'a> StructLint<'a> {
     pub const fn new(struct_defs: &'a Defs<'a>, fields_defs: &'a Defs<'a>) -> Self {
         StructLint {
             struct_defs,
+            variant_defs: None,
+            fields_defs,
+        }
+    }
+
+    /// create a new struct lint that also accepts enums
+    ///
+    /// `variant_defs` lints attributes on the variants of enums
+    #[must_use]
+    pub const fn new_with_variants(
+        struct_defs: &'a Defs<'a>,
+        variant_defs: &'a Defs<'a>,
+        fields_defs: &'a Defs<'a>,
+    ) -> Self {
+        StructLint {
+            struct_defs,
+            variant_defs: Some(variant_defs),
             fields_defs,
         }
     }
@@ -41,19 +59,39 @@ impl Lint<ItemStruct> for StructLint<'_> {
     }
 }
 
+impl Lint<ItemEnum> for StructLint<'_> {
+    fn lint(&self, input: &ItemEnum, c: &mut Collector) {
+        let derive_input = DeriveInput::from(input.clone());
+        let mut subcontext = Context::new_by_ref(c, &derive_input);
+        subcontext.lint(self);
+    }
+}
+
 impl Lint<DeriveInput> for StructLint<'_> {
     fn lint(&self, input: &DeriveInput, c: &mut Collector) {
         let mut subcontext = Context::new_by_ref(c, &input.attrs);
         subcontext.lint(self.struct_defs);
 
-        match &input.data {
-            Data::Struct(s) => {
+        match (&input.data, self.variant_defs) {
+            (Data::Struct(s), _) => {
                 for field in s.fields.iter() {
                     let mut subcontext = Context::new_by_ref(c, &field.attrs);
                     subcontext.lint(self.fields_defs);
                 }
             }
-            _ => c.error(Error::new_spanned(input, "expected a struct")),
+            (Data::Enum(e), Some(variant_defs)) => {
+                for variant in e.variants.iter() {
+                    let mut subcontext = Context::new_by_ref(c, &variant.attrs);
+                    subcontext.lint(variant_defs);
+
+                    for field in variant.fields.iter() {
+                        let mut subcontext = Context::new_by_ref(c, &field.attrs);
+                        subcontext.lint(self.fields_defs);
+                    }
+                }
+            }
+            (_, None) => c.error(Error::new_spanned(input, "expected a struct")),
+            (_, Some(_)) => c.error(Error::new_spanned(input, "expected a struct or an enum")),
         }
     }
 }
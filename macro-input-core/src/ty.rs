@@ -1,4 +1,6 @@
 #[cfg(feature = "legacy")]
+use crate::DefaultValue;
+#[cfg(feature = "legacy")]
 use macro_compose::{Collector, Lint};
 #[cfg(feature = "legacy")]
 use quote::ToTokens;
@@ -19,17 +21,7 @@ pub struct Type {
 #[cfg(feature = "legacy")]
 impl<'a> Lint<Option<&'a Lit>> for Type {
     fn lint(&self, input: &Option<&'a Lit>, c: &mut Collector) {
-        let ty = match self.ty {
-            Types::Any => "anything",
-            Types::Flag => "nothing",
-            Types::Str => "string",
-            Types::ByteStr => "byte string",
-            Types::Byte => "byte",
-            Types::Char => "char",
-            Types::I32 => "i32",
-            Types::F32 => "f32",
-            Types::Bool => "bool",
-        };
+        let ty = self.ty.describe();
 
         match (input, self.ty) {
             (Some(_), Types::Any)
@@ -38,9 +30,19 @@ impl<'a> Lint<Option<&'a Lit>> for Type {
             | (Some(Lit::ByteStr(_)), Types::ByteStr)
             | (Some(Lit::Byte(_)), Types::Byte)
             | (Some(Lit::Char(_)), Types::Char)
-            | (Some(Lit::Int(_)), Types::I32)
-            | (Some(Lit::Float(_)), Types::F32)
             | (Some(Lit::Bool(_)), Types::Bool) => {}
+            (Some(lit @ Lit::Int(_)), ty) if ty.is_integer() || ty.is_float() => {
+                if let Err(e) = DefaultValue::from_lit(*self, Some((*lit).clone())) {
+                    c.error(e);
+                }
+            }
+            (Some(lit @ Lit::Float(_)), ty) if ty.is_float() => {
+                if let Err(e) = DefaultValue::from_lit(*self, Some((*lit).clone())) {
+                    c.error(e);
+                }
+            }
+            // bools can also be flags eg `#[my_input(enabled)]`
+            (None, Types::Bool) => {}
             (None, _) if self.optional => {}
             (Some(lit), _) => c.error(Error::new_spanned(
                 input,
@@ -68,21 +70,122 @@ pub enum Types {
     Byte,
     /// for char
     Char,
+    /// for i8
+    I8,
+    /// for i16
+    I16,
     /// for i32
     I32,
+    /// for i64
+    I64,
+    /// for i128
+    I128,
+    /// for isize
+    Isize,
+    /// for u16
+    U16,
+    /// for u32
+    U32,
+    /// for u64
+    U64,
+    /// for u128
+    U128,
+    /// for usize
+    Usize,
     /// for f32
     F32,
-    /// for bool
+    /// for f64
+    F64,
+    /// for bool, a flag without a value is true eg `#[my_input(enabled)]`
     Bool,
 }
 
+const NUMERIC_TYPES: &[(&str, Types)] = &[
+    ("u8", Types::Byte),
+    ("i8", Types::I8),
+    ("i16", Types::I16),
+    ("i32", Types::I32),
+    ("i64", Types::I64),
+    ("i128", Types::I128),
+    ("isize", Types::Isize),
+    ("u16", Types::U16),
+    ("u32", Types::U32),
+    ("u64", Types::U64),
+    ("u128", Types::U128),
+    ("usize", Types::Usize),
+    ("f32", Types::F32),
+    ("f64", Types::F64),
+];
+
+impl Types {
+    /// find the numeric type named by `path`
+    fn from_primitive(path: &Path) -> Option<Types> {
+        NUMERIC_TYPES
+            .iter()
+            .find(|(name, _)| path.is_ident(name))
+            .map(|(_, ty)| *ty)
+    }
+
+    /// checks whether the type is an integer type
+    #[must_use]
+    pub fn is_integer(self) -> bool {
+        matches!(
+            self,
+            Types::Byte
+                | Types::I8
+                | Types::I16
+                | Types::I32
+                | Types::I64
+                | Types::I128
+                | Types::Isize
+                | Types::U16
+                | Types::U32
+                | Types::U64
+                | Types::U128
+                | Types::Usize
+        )
+    }
+
+    /// checks whether the type is a floating point type
+    #[must_use]
+    pub fn is_float(self) -> bool {
+        matches!(self, Types::F32 | Types::F64)
+    }
+
+    /// describe the type in error messages and help texts
+    pub(crate) const fn describe(self) -> &'static str {
+        match self {
+            Types::Any => "anything",
+            Types::Flag => "nothing",
+            Types::Str => "string",
+            Types::ByteStr => "byte string",
+            Types::Byte => "u8",
+            Types::Char => "char",
+            Types::I8 => "i8",
+            Types::I16 => "i16",
+            Types::I32 => "i32",
+            Types::I64 => "i64",
+            Types::I128 => "i128",
+            Types::Isize => "isize",
+            Types::U16 => "u16",
+            Types::U32 => "u32",
+            Types::U64 => "u64",
+            Types::U128 => "u128",
+            Types::Usize => "usize",
+            Types::F32 => "f32",
+            Types::F64 => "f64",
+            Types::Bool => "bool",
+        }
+    }
+}
+
 impl TryFrom<&syn::Type> for Type {
     type Error = Error;
 
     fn try_from(ty: &syn::Type) -> Result<Self, Self::Error> {
         let byte_vec_path: Path = parse_quote!(Vec<u8>);
 
-        let error = || Err(Error::new_spanned(&ty, "unexpected type"));
+        let error = || Err(Error::new_spanned(ty, "unexpected type"));
 
         match ty {
             syn::Type::Path(p) => {
@@ -96,50 +199,38 @@ impl TryFrom<&syn::Type> for Type {
                         ty: Types::ByteStr,
                         optional: false,
                     })
-                } else if p.path.is_ident("u8") {
-                    Ok(Type {
-                        ty: Types::Byte,
-                        optional: false,
-                    })
                 } else if p.path.is_ident("char") {
                     Ok(Type {
                         ty: Types::Char,
                         optional: false,
                     })
-                } else if p.path.is_ident("i32") {
+                } else if p.path.is_ident("bool") {
                     Ok(Type {
-                        ty: Types::I32,
+                        ty: Types::Bool,
                         optional: false,
                     })
-                } else if p.path.is_ident("f32") {
+                } else if is_flag(&p.path) {
                     Ok(Type {
-                        ty: Types::F32,
-                        optional: false,
+                        ty: Types::Flag,
+                        optional: true,
                     })
-                } else if p.path.is_ident("bool") {
+                } else if let Some(ty) = Types::from_primitive(&p.path) {
                     Ok(Type {
-                        ty: Types::Bool,
+                        ty,
                         optional: false,
                     })
                 } else {
-                    if p.path.segments.len() == 1 {
-                        let segment = p.path.segments.first().unwrap();
-                        if segment.ident == "Option" {
-                            if let PathArguments::AngleBracketed(args) = &segment.arguments {
-                                if let Some(GenericArgument::Type(ty)) = args.args.first() {
-                                    return Type::try_from(ty).and_then(|ty| {
-                                        if ty.optional {
-                                            error()
-                                        } else {
-                                            Ok(Type {
-                                                ty: ty.ty,
-                                                optional: true,
-                                            })
-                                        }
-                                    });
-                                }
+                    if let Some(ty) = option_inner(&p.path) {
+                        return Type::try_from(ty).and_then(|ty| {
+                            if ty.optional {
+                                error()
+                            } else {
+                                Ok(Type {
+                                    ty: ty.ty,
+                                    optional: true,
+                                })
                             }
-                        }
+                        });
                     }
                     error()
                 }
@@ -152,3 +243,37 @@ impl TryFrom<&syn::Type> for Type {
         }
     }
 }
+
+/// checks whether the path is `Flag` or `macro_input::Flag`
+fn is_flag(path: &Path) -> bool {
+    let segments = path.segments.iter().map(|s| &s.ident).collect::<Vec<_>>();
+    let is_flag = match segments.as_slice() {
+        [flag] => path.leading_colon.is_none() && *flag == "Flag",
+        [krate, flag] => *krate == "macro_input" && *flag == "Flag",
+        _ => false,
+    };
+    is_flag && path.segments.last().unwrap().arguments.is_empty()
+}
+
+/// get `T` from `Option<T>`, `std::option::Option<T>` or `core::option::Option<T>`
+fn option_inner(path: &Path) -> Option<&syn::Type> {
+    let segments = path.segments.iter().map(|s| &s.ident).collect::<Vec<_>>();
+    let is_option = match segments.as_slice() {
+        [option] => path.leading_colon.is_none() && *option == "Option",
+        [krate, module, option] => {
+            (*krate == "std" || *krate == "core") && *module == "option" && *option == "Option"
+        }
+        _ => false,
+    };
+    if !is_option {
+        return None;
+    }
+
+    let segment = path.segments.last().unwrap();
+    if let PathArguments::AngleBracketed(args) = &segment.arguments {
+        if let Some(GenericArgument::Type(ty)) = args.args.first() {
+            return Some(ty);
+        }
+    }
+    None
+}
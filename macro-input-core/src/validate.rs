@@ -0,0 +1,118 @@
+//! built-in validators for checking values after parsing
+//!
+//! Every validator takes the value and the span of the meta it was parsed from and returns an error pointing at that span.
+//! Derived inputs use them for `#[macro_input(range = "1..=10")]`, `#[macro_input(one_of = [...])]`,
+//! `#[macro_input(non_empty)]` and `#[macro_input(ident)]`.
+//! # Example
+//! ```
+//! # use macro_input_core as macro_input;
+//! use macro_input::{validate, DefaultValue, Def, Spanned};
+//! use syn::{parse_quote, Attribute};
+//!
+//! # fn main() -> syn::Result<()> {
+//! const TIMEOUT_FIELD: Def = Def::new("foo", "timeout", true, DefaultValue::U32(None));
+//!
+//! let attr: Attribute = parse_quote!(#[foo(timeout = 0)]);
+//! let timeout = TIMEOUT_FIELD.get_value::<Spanned<u32>>(&[attr])?;
+//! let err = validate::range(&*timeout, timeout.span(), 1..=10).unwrap_err();
+//! assert_eq!(err.to_string(), "expected a value in 1..=10, got 0");
+//! # Ok(())
+//! # }
+//! ```
+use proc_macro2::Span;
+use std::{
+    fmt::{Debug, Display},
+    ops::{Bound, RangeBounds},
+};
+use syn::{ext::IdentExt, parse::Parser, Error, Ident, Result};
+
+/// check that a number is in a range eg `1..=10`
+///
+/// # Errors
+/// returns an error if the value is outside of the range
+pub fn range<T: PartialOrd + Display>(
+    value: &T,
+    span: Span,
+    range: impl RangeBounds<T>,
+) -> Result<()> {
+    if range.contains(value) {
+        return Ok(());
+    }
+
+    let start = match range.start_bound() {
+        Bound::Included(start) | Bound::Excluded(start) => start.to_string(),
+        Bound::Unbounded => String::new(),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => format!("={}", end),
+        Bound::Excluded(end) => end.to_string(),
+        Bound::Unbounded => String::new(),
+    };
+    Err(Error::new(
+        span,
+        format!("expected a value in {}..{}, got {}", start, end, value),
+    ))
+}
+
+/// check that a value is one of `values` eg `["GET", "POST"]`
+/// ```
+/// # use macro_input_core as macro_input;
+/// use macro_input::validate;
+/// use proc_macro2::Span;
+///
+/// let method = String::from("PUT");
+/// let err = validate::one_of(&method, Span::call_site(), &["GET", "POST"]).unwrap_err();
+/// assert_eq!(err.to_string(), r#"expected one of: "GET", "POST""#);
+/// ```
+///
+/// # Errors
+/// returns an error if the value isn't one of `values`
+pub fn one_of<T: PartialEq<V>, V: Debug>(value: &T, span: Span, values: &[V]) -> Result<()> {
+    if values.iter().any(|v| value == v) {
+        return Ok(());
+    }
+
+    let values = values
+        .iter()
+        .map(|v| format!("{:?}", v))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(Error::new(span, format!("expected one of: {}", values)))
+}
+
+/// check that a string isn't empty
+///
+/// # Errors
+/// returns an error if the string is empty
+pub fn non_empty<T: AsRef<str>>(value: &T, span: Span) -> Result<()> {
+    if value.as_ref().is_empty() {
+        Err(Error::new(span, "expected a non-empty string"))
+    } else {
+        Ok(())
+    }
+}
+
+/// check that a string is a valid identifier eg `foo` or `r#type` but not `type` or `3`
+///
+/// # Errors
+/// returns an error if the string isn't an identifier
+pub fn ident<T: AsRef<str>>(value: &T, span: Span) -> Result<()> {
+    let value = value.as_ref();
+    syn::parse_str::<Ident>(value)
+        .map(drop)
+        .map_err(|_| Error::new(span, format!("expected an identifier, got {:?}", value)))
+}
+
+/// check that a string can be used as a key eg `foo` or `type` but not `3`
+///
+/// Unlike [`ident`] keywords are accepted because they can be keys eg `#[foo(crate = "bar")]`.
+///
+/// # Errors
+/// returns an error if the string isn't a key
+pub fn key<T: AsRef<str>>(value: &T, span: Span) -> Result<()> {
+    let value = value.as_ref();
+    Ident::parse_any
+        .parse_str(value)
+        .map(drop)
+        .map_err(|_| Error::new(span, format!("expected a key, got {:?}", value)))
+}
@@ -1,13 +1,17 @@
-use crate::{Type, Types};
+use crate::{FromLit, Type, Types, Value};
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens};
-use syn::{parse_quote, Error, Lit, LitByteStr, Result};
+use syn::{parse_quote, parse_str, Error, Lit, LitByteStr, Result};
 
 #[derive(Clone)]
 /// a default value for a [`Def`](crate::Def)
 pub enum DefaultValue {
     /// any literal
     Any(Option<Lit>),
+    /// any value in its source form eg `"3"` or `"some::CONST"`
+    AnyStr(Option<&'static str>),
+    /// any value constructed by a function eg `|| parse_quote!(some::CONST)`, used for the values of derived inputs
+    AnyValue(Option<fn() -> Value>),
     /// a flag that doesn't have a value eg `#[my_input(enabled)]`
     Flag,
     /// a string
@@ -22,10 +26,32 @@ pub enum DefaultValue {
     Byte(Option<u8>),
     /// a char
     Char(Option<char>),
+    /// a i8
+    I8(Option<i8>),
+    /// a i16
+    I16(Option<i16>),
     /// a i32
     I32(Option<i32>),
+    /// a i64
+    I64(Option<i64>),
+    /// a i128
+    I128(Option<i128>),
+    /// a isize
+    Isize(Option<isize>),
+    /// a u16
+    U16(Option<u16>),
+    /// a u32
+    U32(Option<u32>),
+    /// a u64
+    U64(Option<u64>),
+    /// a u128
+    U128(Option<u128>),
+    /// a usize
+    Usize(Option<usize>),
     /// a f32
     F32(Option<f32>),
+    /// a f64
+    F64(Option<f64>),
     /// a bool
     Bool(Option<bool>),
 }
@@ -38,66 +64,23 @@ impl DefaultValue {
         match ty.ty {
             Types::Any => Ok(DefaultValue::Any(lit)),
             Types::Flag => Ok(DefaultValue::Flag),
-            Types::Str => lit
-                .map(|lit| {
-                    if let Lit::Str(v) = lit {
-                        Ok(v.value())
-                    } else {
-                        Err(Error::new(lit.span(), "expected string"))
-                    }
-                })
-                .transpose()
-                .map(DefaultValue::String),
-            Types::ByteStr => lit
-                .map(|lit| {
-                    if let Lit::ByteStr(v) = lit {
-                        Ok(v.value())
-                    } else {
-                        Err(Error::new(lit.span(), "expected bytes"))
-                    }
-                })
-                .transpose()
-                .map(DefaultValue::ByteString),
-            Types::Byte => lit
-                .map(|lit| {
-                    if let Lit::Byte(v) = lit {
-                        Ok(v.value())
-                    } else {
-                        Err(Error::new(lit.span(), "expected byte"))
-                    }
-                })
-                .transpose()
-                .map(DefaultValue::Byte),
-            Types::Char => lit
-                .map(|lit| {
-                    if let Lit::Char(v) = lit {
-                        Ok(v.value())
-                    } else {
-                        Err(Error::new(lit.span(), "expected char"))
-                    }
-                })
-                .transpose()
-                .map(DefaultValue::Char),
-            Types::I32 => lit
-                .map(|lit| {
-                    if let Lit::Int(v) = lit {
-                        v.base10_parse()
-                    } else {
-                        Err(Error::new(lit.span(), "expected i32"))
-                    }
-                })
-                .transpose()
-                .map(DefaultValue::I32),
-            Types::F32 => lit
-                .map(|lit| {
-                    if let Lit::Int(v) = lit {
-                        v.base10_parse()
-                    } else {
-                        Err(Error::new(lit.span(), "expected f32"))
-                    }
-                })
-                .transpose()
-                .map(DefaultValue::F32),
+            Types::Str => from_lit(lit).map(DefaultValue::String),
+            Types::ByteStr => from_lit(lit).map(DefaultValue::ByteString),
+            Types::Byte => from_lit(lit).map(DefaultValue::Byte),
+            Types::Char => from_lit(lit).map(DefaultValue::Char),
+            Types::I8 => from_lit(lit).map(DefaultValue::I8),
+            Types::I16 => from_lit(lit).map(DefaultValue::I16),
+            Types::I32 => from_lit(lit).map(DefaultValue::I32),
+            Types::I64 => from_lit(lit).map(DefaultValue::I64),
+            Types::I128 => from_lit(lit).map(DefaultValue::I128),
+            Types::Isize => from_lit(lit).map(DefaultValue::Isize),
+            Types::U16 => from_lit(lit).map(DefaultValue::U16),
+            Types::U32 => from_lit(lit).map(DefaultValue::U32),
+            Types::U64 => from_lit(lit).map(DefaultValue::U64),
+            Types::U128 => from_lit(lit).map(DefaultValue::U128),
+            Types::Usize => from_lit(lit).map(DefaultValue::Usize),
+            Types::F32 => from_lit(lit).map(DefaultValue::F32),
+            Types::F64 => from_lit(lit).map(DefaultValue::F64),
             Types::Bool => lit
                 .map(|lit| {
                     if let Lit::Bool(v) = lit {
@@ -125,6 +108,8 @@ impl DefaultValue {
     pub fn has_default_data(&self) -> bool {
         match self {
             DefaultValue::Any(val) => val.is_some(),
+            DefaultValue::AnyStr(val) => val.is_some(),
+            DefaultValue::AnyValue(val) => val.is_some(),
             DefaultValue::Flag => false,
             DefaultValue::Str(val) => val.is_some(),
             DefaultValue::String(val) => val.is_some(),
@@ -132,44 +117,99 @@ impl DefaultValue {
             DefaultValue::ByteString(val) => val.is_some(),
             DefaultValue::Byte(val) => val.is_some(),
             DefaultValue::Char(val) => val.is_some(),
+            DefaultValue::I8(val) => val.is_some(),
+            DefaultValue::I16(val) => val.is_some(),
             DefaultValue::I32(val) => val.is_some(),
+            DefaultValue::I64(val) => val.is_some(),
+            DefaultValue::I128(val) => val.is_some(),
+            DefaultValue::Isize(val) => val.is_some(),
+            DefaultValue::U16(val) => val.is_some(),
+            DefaultValue::U32(val) => val.is_some(),
+            DefaultValue::U64(val) => val.is_some(),
+            DefaultValue::U128(val) => val.is_some(),
+            DefaultValue::Usize(val) => val.is_some(),
             DefaultValue::F32(val) => val.is_some(),
+            DefaultValue::F64(val) => val.is_some(),
             DefaultValue::Bool(val) => val.is_some(),
         }
     }
 
-    pub(crate) fn as_lit(&self) -> Option<Lit> {
+    /// the default value as a value
+    ///
+    /// # Errors
+    /// returns an error if the source form of an [`AnyStr`](DefaultValue::AnyStr) isn't a valid value
+    pub(crate) fn as_value(&self) -> Result<Option<Value>> {
+        match self {
+            DefaultValue::AnyStr(val) => val
+                .map(|v| {
+                    parse_str(v).map_err(|_| {
+                        Error::new(Span::call_site(), format!("invalid default value: {}", v))
+                    })
+                })
+                .transpose(),
+            DefaultValue::AnyValue(val) => Ok(val.map(|f| f())),
+            value => Ok(value.as_lit().map(Value::Lit)),
+        }
+    }
+
+    fn as_lit(&self) -> Option<Lit> {
         match self {
             DefaultValue::Flag => None,
             DefaultValue::Any(val) => val.clone(),
+            DefaultValue::AnyStr(val) => val.and_then(|v| parse_str(v).ok()),
+            DefaultValue::AnyValue(val) => val.and_then(|f| match f() {
+                Value::Lit(lit) => Some(lit),
+                _ => None,
+            }),
             DefaultValue::Str(val) => val.map(|v| parse_quote!(#v)),
             DefaultValue::String(val) => val.as_ref().map(|v| parse_quote!(#v)),
             DefaultValue::ByteStr(val) => val.map(|v| {
-                let lbs = LitByteStr::new(&v, Span::call_site());
+                let lbs = LitByteStr::new(v, Span::call_site());
                 parse_quote!(#lbs)
             }),
             DefaultValue::ByteString(val) => val.as_ref().map(|v| {
-                let lbs = LitByteStr::new(&v, Span::call_site());
+                let lbs = LitByteStr::new(v, Span::call_site());
                 parse_quote!(#lbs)
             }),
             DefaultValue::Byte(val) => val.map(|v| parse_quote!(#v)),
             DefaultValue::Char(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::I8(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::I16(val) => val.map(|v| parse_quote!(#v)),
             DefaultValue::I32(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::I64(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::I128(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::Isize(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::U16(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::U32(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::U64(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::U128(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::Usize(val) => val.map(|v| parse_quote!(#v)),
             DefaultValue::F32(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::F64(val) => val.map(|v| parse_quote!(#v)),
             DefaultValue::Bool(val) => val.map(|v| parse_quote!(#v)),
         }
     }
 }
 
+/// parse an optional literal into an optional value
+fn from_lit<V: FromLit>(lit: Option<Lit>) -> Result<Option<V>> {
+    lit.map(|lit| V::from(Some(lit))).transpose()
+}
+
 impl From<DefaultValue> for Option<Lit> {
     fn from(val: DefaultValue) -> Self {
         match val {
             DefaultValue::Flag => None,
             DefaultValue::Any(val) => val,
+            DefaultValue::AnyStr(val) => val.and_then(|v| parse_str(v).ok()),
+            DefaultValue::AnyValue(val) => val.and_then(|f| match f() {
+                Value::Lit(lit) => Some(lit),
+                _ => None,
+            }),
             DefaultValue::Str(val) => val.map(|v| parse_quote!(#v)),
             DefaultValue::String(val) => val.map(|v| parse_quote!(#v)),
             DefaultValue::ByteStr(val) => val.map(|v| {
-                let lbs = LitByteStr::new(&v, Span::call_site());
+                let lbs = LitByteStr::new(v, Span::call_site());
                 parse_quote!(#lbs)
             }),
             DefaultValue::ByteString(val) => val.map(|v| {
@@ -178,8 +218,19 @@ impl From<DefaultValue> for Option<Lit> {
             }),
             DefaultValue::Byte(val) => val.map(|v| parse_quote!(#v)),
             DefaultValue::Char(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::I8(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::I16(val) => val.map(|v| parse_quote!(#v)),
             DefaultValue::I32(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::I64(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::I128(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::Isize(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::U16(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::U32(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::U64(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::U128(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::Usize(val) => val.map(|v| parse_quote!(#v)),
             DefaultValue::F32(val) => val.map(|v| parse_quote!(#v)),
+            DefaultValue::F64(val) => val.map(|v| parse_quote!(#v)),
             DefaultValue::Bool(val) => val.map(|v| parse_quote!(#v)),
         }
     }
@@ -188,14 +239,27 @@ impl From<DefaultValue> for Option<Lit> {
 impl From<&DefaultValue> for Types {
     fn from(value: &DefaultValue) -> Self {
         match value {
-            DefaultValue::Any(_) => Types::Any,
+            DefaultValue::Any(_) | DefaultValue::AnyStr(_) | DefaultValue::AnyValue(_) => {
+                Types::Any
+            }
             DefaultValue::Flag => Types::Flag,
             DefaultValue::Str(_) | DefaultValue::String(_) => Types::Str,
             DefaultValue::ByteStr(_) | DefaultValue::ByteString(_) => Types::ByteStr,
             DefaultValue::Byte(_) => Types::Byte,
             DefaultValue::Char(_) => Types::Char,
+            DefaultValue::I8(_) => Types::I8,
+            DefaultValue::I16(_) => Types::I16,
             DefaultValue::I32(_) => Types::I32,
+            DefaultValue::I64(_) => Types::I64,
+            DefaultValue::I128(_) => Types::I128,
+            DefaultValue::Isize(_) => Types::Isize,
+            DefaultValue::U16(_) => Types::U16,
+            DefaultValue::U32(_) => Types::U32,
+            DefaultValue::U64(_) => Types::U64,
+            DefaultValue::U128(_) => Types::U128,
+            DefaultValue::Usize(_) => Types::Usize,
             DefaultValue::F32(_) => Types::F32,
+            DefaultValue::F64(_) => Types::F64,
             DefaultValue::Bool(_) => Types::Bool,
         }
     }
@@ -213,8 +277,19 @@ impl ToTokens for DefaultValue {
 
         let tts = match self {
             DefaultValue::Any(v) => {
+                let data = map_literal(&v.as_ref().map(|v| quote!(|| ::syn::parse_quote!(#v))));
+                quote!(::macro_input::DefaultValue::AnyValue(#data))
+            }
+            DefaultValue::AnyStr(v) => {
                 let data = map_literal(v);
-                quote!(::macro_input::DefaultValue::Any(#data))
+                quote!(::macro_input::DefaultValue::AnyStr(#data))
+            }
+            DefaultValue::AnyValue(v) => {
+                let data = map_literal(&v.map(|f| {
+                    let value = f();
+                    quote!(|| ::syn::parse_quote!(#value))
+                }));
+                quote!(::macro_input::DefaultValue::AnyValue(#data))
             }
             DefaultValue::Flag => quote!(::macro_input::DefaultValue::Flag),
             DefaultValue::Str(v) => {
@@ -241,14 +316,58 @@ impl ToTokens for DefaultValue {
                 let data = map_literal(v);
                 quote!(::macro_input::DefaultValue::Char(#data))
             }
+            DefaultValue::I8(v) => {
+                let data = map_literal(v);
+                quote!(::macro_input::DefaultValue::I8(#data))
+            }
+            DefaultValue::I16(v) => {
+                let data = map_literal(v);
+                quote!(::macro_input::DefaultValue::I16(#data))
+            }
             DefaultValue::I32(v) => {
                 let data = map_literal(v);
                 quote!(::macro_input::DefaultValue::I32(#data))
             }
+            DefaultValue::I64(v) => {
+                let data = map_literal(v);
+                quote!(::macro_input::DefaultValue::I64(#data))
+            }
+            DefaultValue::I128(v) => {
+                let data = map_literal(v);
+                quote!(::macro_input::DefaultValue::I128(#data))
+            }
+            DefaultValue::Isize(v) => {
+                let data = map_literal(v);
+                quote!(::macro_input::DefaultValue::Isize(#data))
+            }
+            DefaultValue::U16(v) => {
+                let data = map_literal(v);
+                quote!(::macro_input::DefaultValue::U16(#data))
+            }
+            DefaultValue::U32(v) => {
+                let data = map_literal(v);
+                quote!(::macro_input::DefaultValue::U32(#data))
+            }
+            DefaultValue::U64(v) => {
+                let data = map_literal(v);
+                quote!(::macro_input::DefaultValue::U64(#data))
+            }
+            DefaultValue::U128(v) => {
+                let data = map_literal(v);
+                quote!(::macro_input::DefaultValue::U128(#data))
+            }
+            DefaultValue::Usize(v) => {
+                let data = map_literal(v);
+                quote!(::macro_input::DefaultValue::Usize(#data))
+            }
             DefaultValue::F32(v) => {
                 let data = map_literal(v);
                 quote!(::macro_input::DefaultValue::F32(#data))
             }
+            DefaultValue::F64(v) => {
+                let data = map_literal(v);
+                quote!(::macro_input::DefaultValue::F64(#data))
+            }
             DefaultValue::Bool(v) => {
                 let data = map_literal(v);
                 quote!(::macro_input::DefaultValue::Bool(#data))
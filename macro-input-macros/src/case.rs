@@ -0,0 +1,40 @@
+use heck::{CamelCase, MixedCase, ShoutySnekCase, SnekCase};
+use macro_input_core::{from_str_table, FromLit};
+use syn::{Lit, Result};
+
+/// a case convention for `rename_all`
+#[derive(Clone, Copy)]
+pub enum Case {
+    Camel,
+    Snake,
+    Pascal,
+    ScreamingSnake,
+    Lower,
+}
+
+const CASES: &[(&str, Case)] = &[
+    ("camelCase", Case::Camel),
+    ("snake_case", Case::Snake),
+    ("PascalCase", Case::Pascal),
+    ("SCREAMING_SNAKE_CASE", Case::ScreamingSnake),
+    ("lowercase", Case::Lower),
+];
+
+impl Case {
+    /// convert a name eg `some_field` or `SomeVariant` to this case
+    pub fn apply(self, name: &str) -> String {
+        match self {
+            Case::Camel => name.to_mixed_case(),
+            Case::Snake => name.to_snek_case(),
+            Case::Pascal => name.to_camel_case(),
+            Case::ScreamingSnake => name.TO_SHOUTY_SNEK_CASE(),
+            Case::Lower => name.to_lowercase(),
+        }
+    }
+}
+
+impl FromLit for Case {
+    fn from(lit: Option<Lit>) -> Result<Self> {
+        from_str_table(lit, CASES)
+    }
+}
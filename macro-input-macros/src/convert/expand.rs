@@ -1,6 +1,15 @@
-use crate::{field_name, mod_name};
+use crate::{
+    bounded_generics, check_unknown, default_expr, emit_generics, field_ident, has_nested_fields,
+    is_emitted, is_newtype, is_optional, is_parsed, is_repeated, is_skipped, mod_name, rename_all,
+    validators, variant_mod_name, variant_name, VALIDATE_FIELD,
+};
 use macro_compose::{Collector, Expand};
-use syn::{parse_quote, Data, DeriveInput, Expr, FieldValue, Fields, ItemImpl};
+use proc_macro2::Ident;
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, punctuated::Punctuated, token::Comma, Arm, Data, DeriveInput, Expr, Field,
+    FieldValue, Fields, Index, ItemImpl, Path, Stmt, Variant,
+};
 
 pub struct TryFromAttributes;
 
@@ -9,47 +18,607 @@ impl Expand<DeriveInput> for TryFromAttributes {
 
     fn expand(&self, input: &DeriveInput, _: &mut Collector) -> Option<Self::Output> {
         let ident = &input.ident;
+        let generics = bounded_generics(input);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-        let fields = match &input.data {
-            Data::Struct(s) => &s.fields,
-            _ => unreachable!(),
-        };
+        Some(parse_quote!(
+            impl #impl_generics ::core::convert::TryFrom<&[::syn::Attribute]> for #ident #ty_generics
+            #where_clause
+            {
+                type Error = ::syn::Error;
 
-        let mod_name = mod_name(input);
-        let values = fields.iter().map(|f| -> Expr {
-            let (_, ident) = field_name(f);
-            parse_quote!(#mod_name::#ident.get_value::<>(attrs)?)
-        });
-
-        let block: Expr = match fields {
-            Fields::Named(named) => {
-                let values = values
-                    .zip(
-                        named
-                            .named
-                            .iter()
-                            .map(|f| f.ident.as_ref().cloned().unwrap()),
+                fn try_from(attrs: &[::syn::Attribute]) -> ::syn::Result<Self> {
+                    Self::try_from_partial(attrs).into_result()
+                }
+            }
+        ))
+    }
+}
+
+pub struct FromMeta;
+
+impl Expand<DeriveInput> for FromMeta {
+    type Output = ItemImpl;
+
+    fn expand(&self, input: &DeriveInput, _: &mut Collector) -> Option<Self::Output> {
+        let ident = &input.ident;
+        let generics = bounded_generics(input);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        Some(parse_quote!(
+            impl #impl_generics ::macro_input::FromMeta for #ident #ty_generics #where_clause {
+                fn from(meta: ::std::option::Option<::macro_input::Meta>) -> ::syn::Result<Self> {
+                    Self::from_meta_partial(meta).into_result()
+                }
+
+                fn from_list(list: ::macro_input::MetaList) -> ::syn::Result<::std::vec::Vec<Self>> {
+                    <Self as ::macro_input::FromMeta>::from(
+                        ::std::option::Option::Some(::macro_input::Meta::List(list)),
                     )
-                    .map(|(value, ident)| -> FieldValue { parse_quote!(#ident: #value) });
+                    .map(|value| ::std::vec![value])
+                }
+            }
+        ))
+    }
+}
+
+pub struct Partial;
+
+impl Expand<DeriveInput> for Partial {
+    type Output = ItemImpl;
+
+    fn expand(&self, input: &DeriveInput, c: &mut Collector) -> Option<Self::Output> {
+        let ident = &input.ident;
+        let generics = bounded_generics(input);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
+        let mod_name = mod_name(input);
+        let unknown = check_unknown(
+            input,
+            parse_quote!(#mod_name::FIELD_DEFS.check_unknown(attrs)),
+        );
+        let unknown_nested = check_unknown(
+            input,
+            parse_quote!(#mod_name::FIELD_DEFS.check_unknown_nested(&list)),
+        );
+        // the variants of enums don't have constraints between them
+        let (constraints, constraints_nested): (Option<Stmt>, Option<Stmt>) = match &input.data {
+            Data::Struct(_) => (
+                Some(parse_quote!(partial.ok(#mod_name::FIELD_DEFS.check_constraints(attrs));)),
+                Some(parse_quote!(
+                    partial.ok(#mod_name::FIELD_DEFS.check_constraints_nested(&list));
+                )),
+            ),
+            _ => (None, None),
+        };
+        let validate = match VALIDATE_FIELD.get_all_values::<Path>(&input.attrs) {
+            Ok(paths) => paths,
+            Err(e) => {
+                c.error(e);
+                return None;
+            }
+        };
+        let validate = validate
+            .into_iter()
+            .map(|path| -> Stmt {
                 parse_quote!(
-                    Self {
-                        #(#values),*
+                    if let ::std::option::Option::Some(value) = &value {
+                        partial.ok(#path(value));
                     }
                 )
+            })
+            .collect::<Vec<_>>();
+        let (value, nested_value): (Expr, Expr) = match &input.data {
+            Data::Struct(s) => {
+                let value = |nested| {
+                    construct(
+                        &parse_quote!(Self),
+                        &s.fields,
+                        default_expr(&input.attrs),
+                        |f, ident, fallback| {
+                            field_value(&parse_quote!(#mod_name::#ident), f, nested, fallback)
+                        },
+                    )
+                };
+                (value(false), value(true))
+            }
+            Data::Enum(e) => {
+                let select = select_variant(input, &e.variants);
+                (
+                    parse_quote!(
+                        match #mod_name::FIELD_DEFS.get_one_of_spanned(attrs, span) {
+                            ::std::result::Result::Ok((def, meta)) => #select,
+                            ::std::result::Result::Err(e) => {
+                                partial.push_error(e);
+                                ::std::option::Option::None
+                            }
+                        }
+                    ),
+                    parse_quote!(
+                        match #mod_name::FIELD_DEFS.get_one_of_nested(&list) {
+                            ::std::result::Result::Ok((def, meta)) => #select,
+                            ::std::result::Result::Err(e) => {
+                                partial.push_error(e);
+                                ::std::option::Option::None
+                            }
+                        }
+                    ),
+                )
             }
-            Fields::Unnamed(_) => parse_quote!(Self (#(#values),*)),
-            Fields::Unit => parse_quote!(Self),
+            _ => unreachable!(),
         };
 
         Some(parse_quote!(
-            impl ::core::convert::TryFrom<&[::syn::Attribute]> for #ident {
-                type Error = ::syn::Error;
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// parse the attributes without stopping at the first error
+                ///
+                /// All errors are collected and fields that can't be parsed fall back to their default value if they have one.
+                pub fn try_from_partial(attrs: &[::syn::Attribute]) -> ::macro_input::Partial<Self> {
+                    Self::try_from_partial_spanned(attrs, ::proc_macro2::Span::call_site())
+                }
 
-                fn try_from(attrs: &[::syn::Attribute]) -> ::syn::Result<Self> {
-                    ::std::result::Result::Ok(#block)
+                /// parse the attributes, errors for missing required fields point at `span` eg the ident of the item
+                /// unless there's an attribute for the input
+                pub fn try_from_spanned(
+                    attrs: &[::syn::Attribute],
+                    span: ::proc_macro2::Span,
+                ) -> ::syn::Result<Self> {
+                    Self::try_from_partial_spanned(attrs, span).into_result()
+                }
+
+                /// parse the attributes without stopping at the first error, errors for missing required fields point
+                /// at `span` unless there's an attribute for the input
+                #[allow(unused_variables)]
+                pub fn try_from_partial_spanned(
+                    attrs: &[::syn::Attribute],
+                    span: ::proc_macro2::Span,
+                ) -> ::macro_input::Partial<Self> {
+                    let mut partial = ::macro_input::Partial::new();
+                    #unknown
+                    #constraints
+                    let value = #value;
+                    #(#validate)*
+                    partial.value = value;
+                    partial
+                }
+
+                /// parse a meta without stopping at the first error
+                ///
+                /// All errors are collected and fields that can't be parsed fall back to their default value if they have one.
+                pub fn from_meta_partial(
+                    meta: ::std::option::Option<::macro_input::Meta>,
+                ) -> ::macro_input::Partial<Self> {
+                    Self::from_meta_partial_spanned(meta, ::proc_macro2::Span::call_site())
+                }
+
+                /// parse a meta without stopping at the first error, the error for a missing meta points at `span`
+                pub fn from_meta_partial_spanned(
+                    meta: ::std::option::Option<::macro_input::Meta>,
+                    span: ::proc_macro2::Span,
+                ) -> ::macro_input::Partial<Self> {
+                    let mut partial = ::macro_input::Partial::new();
+                    let list = match meta {
+                        ::std::option::Option::Some(meta) => {
+                            <::macro_input::MetaList as ::macro_input::FromMeta>::from(
+                                ::std::option::Option::Some(meta),
+                            )
+                        }
+                        ::std::option::Option::None => ::std::result::Result::Err(
+                            ::syn::Error::new(span, "expected a list"),
+                        ),
+                    };
+                    let list = match list {
+                        ::std::result::Result::Ok(list) => list,
+                        ::std::result::Result::Err(e) => {
+                            partial.push_error(e);
+                            return partial;
+                        }
+                    };
+                    #unknown_nested
+                    #constraints_nested
+                    let value = #nested_value;
+                    #(#validate)*
+                    partial.value = value;
+                    partial
+                }
+            }
+        ))
+    }
+}
+
+pub struct FromMetaBox;
+
+impl Expand<DeriveInput> for FromMetaBox {
+    type Output = ItemImpl;
+
+    fn expand(&self, input: &DeriveInput, _: &mut Collector) -> Option<Self::Output> {
+        let ident = &input.ident;
+        let generics = bounded_generics(input);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        Some(parse_quote!(
+            impl #impl_generics ::macro_input::FromMeta for ::std::boxed::Box<#ident #ty_generics>
+            #where_clause
+            {
+                fn from(meta: ::std::option::Option<::macro_input::Meta>) -> ::syn::Result<Self> {
+                    <#ident #ty_generics as ::macro_input::FromMeta>::from(meta)
+                        .map(::std::boxed::Box::new)
+                }
+
+                fn from_list(list: ::macro_input::MetaList) -> ::syn::Result<::std::vec::Vec<Self>> {
+                    <#ident #ty_generics as ::macro_input::FromMeta>::from_list(list)
+                        .map(|values| values.into_iter().map(::std::boxed::Box::new).collect())
+                }
+            }
+        ))
+    }
+}
+
+pub struct Parse;
+
+impl Expand<DeriveInput> for Parse {
+    type Output = ItemImpl;
+
+    fn expand(&self, input: &DeriveInput, _: &mut Collector) -> Option<Self::Output> {
+        if !is_parsed(input) {
+            return None;
+        }
+
+        let ident = &input.ident;
+        let generics = bounded_generics(input);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        Some(parse_quote!(
+            impl #impl_generics ::syn::parse::Parse for #ident #ty_generics #where_clause {
+                fn parse(input: ::syn::parse::ParseStream) -> ::syn::Result<Self> {
+                    let span = input.span();
+                    let nested = input.parse_terminated::<_, ::syn::Token![,]>(
+                        <::macro_input::NestedMeta as ::syn::parse::Parse>::parse,
+                    )?;
+                    Self::from_nested_spanned(&nested.into_iter().collect::<::std::vec::Vec<_>>(), span)
+                }
+            }
+        ))
+    }
+}
+
+pub struct ToMeta;
+
+impl Expand<DeriveInput> for ToMeta {
+    type Output = ItemImpl;
+
+    fn expand(&self, input: &DeriveInput, _: &mut Collector) -> Option<Self::Output> {
+        if !is_emitted(input) {
+            return None;
+        }
+
+        let ident = &input.ident;
+        let generics = emit_generics(input);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        Some(parse_quote!(
+            impl #impl_generics ::macro_input::ToMeta for #ident #ty_generics #where_clause {
+                fn to_meta(&self, path: &::syn::Path) -> ::std::option::Option<::macro_input::Meta> {
+                    let mut list = self.to_meta_list(false);
+                    list.path = ::std::clone::Clone::clone(path);
+                    ::std::option::Option::Some(::macro_input::Meta::List(list))
+                }
+            }
+        ))
+    }
+}
+
+pub struct ToMetaBox;
+
+impl Expand<DeriveInput> for ToMetaBox {
+    type Output = ItemImpl;
+
+    fn expand(&self, input: &DeriveInput, _: &mut Collector) -> Option<Self::Output> {
+        if !is_emitted(input) {
+            return None;
+        }
+
+        let ident = &input.ident;
+        let generics = emit_generics(input);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        Some(parse_quote!(
+            impl #impl_generics ::macro_input::ToMeta for ::std::boxed::Box<#ident #ty_generics>
+            #where_clause
+            {
+                fn to_meta(&self, path: &::syn::Path) -> ::std::option::Option<::macro_input::Meta> {
+                    <#ident #ty_generics as ::macro_input::ToMeta>::to_meta(self, path)
+                }
+            }
+        ))
+    }
+}
+
+pub struct ToTokens;
+
+impl Expand<DeriveInput> for ToTokens {
+    type Output = ItemImpl;
+
+    fn expand(&self, input: &DeriveInput, _: &mut Collector) -> Option<Self::Output> {
+        if !is_emitted(input) {
+            return None;
+        }
+
+        let ident = &input.ident;
+        let generics = emit_generics(input);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        Some(parse_quote!(
+            impl #impl_generics ::quote::ToTokens for #ident #ty_generics #where_clause {
+                fn to_tokens(&self, tokens: &mut ::proc_macro2::TokenStream) {
+                    let attr = self.to_meta_list(false).into_attribute();
+                    ::quote::ToTokens::to_tokens(&attr, tokens);
                 }
             }
         ))
     }
 }
+
+/// match the `def` of the variant that was found and parse its `meta` into an `Option<Self>`
+fn select_variant(input: &DeriveInput, variants: &Punctuated<Variant, Comma>) -> Expr {
+    let mod_name = mod_name(input);
+
+    let arms = variants.iter().map(|v| -> Arm {
+        let (name, _) = variant_name(v, rename_all(&input.attrs));
+        let ident = &v.ident;
+
+        if is_newtype(v) {
+            parse_quote!(
+                #name => partial
+                    .ok(::macro_input::FromMeta::from(::std::option::Option::Some(meta)))
+                    .map(Self::#ident)
+            )
+        } else if has_nested_fields(v) {
+            let variant_mod_name = variant_mod_name(v);
+            let check_unknown = check_unknown(
+                input,
+                parse_quote!(#mod_name::#variant_mod_name::FIELD_DEFS.check_unknown_nested(&list)),
+            );
+            let value = construct(
+                &parse_quote!(Self::#ident),
+                &v.fields,
+                None,
+                |f, ident, fallback| {
+                    let def = parse_quote!(#mod_name::#variant_mod_name::#ident);
+                    field_value(&def, f, true, fallback)
+                },
+            );
+            parse_quote!(
+                #name => match <::macro_input::MetaList as ::macro_input::FromMeta>::from(
+                    ::std::option::Option::Some(meta),
+                ) {
+                    ::std::result::Result::Ok(list) => {
+                        #check_unknown
+                        partial.ok(
+                            #mod_name::#variant_mod_name::FIELD_DEFS.check_constraints_nested(&list),
+                        );
+                        #value
+                    }
+                    ::std::result::Result::Err(e) => {
+                        partial.push_error(e);
+                        ::std::option::Option::None
+                    }
+                }
+            )
+        } else {
+            parse_quote!(
+                #name => partial
+                    .ok(<::std::option::Option<()> as ::macro_input::FromMeta>::from(
+                        ::std::option::Option::Some(meta),
+                    ))
+                    .map(|_| Self::#ident)
+            )
+        }
+    });
+
+    parse_quote!(
+        match def.name {
+            #(#arms,)*
+            _ => ::core::unreachable!(),
+        }
+    )
+}
+
+/// get the value for a field from the attributes called `attrs` or a nested meta list called `list` as an `Option`
+///
+/// Errors are added to `partial`.
+/// `fallback` is evaluated if the field is missing or has an error.
+/// Without a fallback fields with an error get the value they'd have without any attributes eg their default value,
+/// `None` or an empty `Vec`, required fields are `None`.
+fn field_value(def: &Path, f: &Field, nested: bool, fallback: Option<Expr>) -> Expr {
+    let checks = validators(&f.attrs);
+    if checks.is_empty() {
+        return parse_value(def, f, nested, fallback);
+    }
+
+    // the span is looked up first so that its error is only reported once
+    let span: Expr = if nested {
+        parse_quote!(#def.nested_value_span(&list))
+    } else {
+        parse_quote!(#def.value_span(attrs))
+    };
+    let error_fallback = error_fallback(def, f, fallback.clone());
+    let value = parse_value(def, f, nested, fallback);
+    let checks = quote!(#(partial.ok(#checks);)*);
+    let checks = if is_repeated(&f.ty) {
+        quote!(for value in value { #checks })
+    } else if is_optional(&f.ty) {
+        quote!(if let ::std::option::Option::Some(value) = value { #checks })
+    } else {
+        checks
+    };
+    let ty = &f.ty;
+    parse_quote!(
+        match #span {
+            ::std::result::Result::Ok(value_span) => {
+                let value: ::std::option::Option<#ty> = #value;
+                if let (::std::option::Option::Some(value), ::std::option::Option::Some(span)) =
+                    (&value, value_span)
+                {
+                    #checks
+                }
+                value
+            }
+            ::std::result::Result::Err(e) => {
+                partial.push_error(e);
+                #error_fallback
+            }
+        }
+    )
+}
+
+/// the value for a field as an `Option` without validating it
+fn parse_value(def: &Path, f: &Field, nested: bool, fallback: Option<Expr>) -> Expr {
+    let source: Expr = if nested {
+        parse_quote!(&list)
+    } else {
+        parse_quote!(attrs)
+    };
+
+    let repeated = is_repeated(&f.ty);
+    let value: Expr = match (repeated, nested) {
+        (true, true) => parse_quote!(#def.get_all_nested_values::<>(#source)),
+        (true, false) => parse_quote!(#def.get_all_values::<>(#source)),
+        (false, true) => parse_quote!(#def.get_nested_value::<>(#source)),
+        (false, false) => parse_quote!(#def.get_value_spanned::<>(#source, span)),
+    };
+
+    let fallback = match fallback {
+        Some(fallback) => fallback,
+        None => {
+            let error_fallback = error_fallback(def, f, None);
+            return parse_quote!(
+                match #value {
+                    ::std::result::Result::Ok(value) => ::std::option::Option::Some(value),
+                    ::std::result::Result::Err(e) => {
+                        partial.push_error(e);
+                        #error_fallback
+                    }
+                }
+            );
+        }
+    };
+
+    // `Ok(None)` if the field is missing
+    let found: Expr = if repeated {
+        parse_quote!(
+            #value.map(|values| ::std::option::Option::Some(values).filter(|values| !values.is_empty()))
+        )
+    } else {
+        let meta: Expr = if nested {
+            parse_quote!(#def.get_nested_meta(#source))
+        } else {
+            parse_quote!(#def.get_meta_spanned(#source, span))
+        };
+        parse_quote!(
+            #meta.and_then(|meta| {
+                meta.map(|meta| ::macro_input::FromMeta::from(::std::option::Option::Some(meta)))
+                    .transpose()
+            })
+        )
+    };
+    parse_quote!(
+        match #found {
+            ::std::result::Result::Ok(::std::option::Option::Some(value)) => {
+                ::std::option::Option::Some(value)
+            }
+            ::std::result::Result::Ok(::std::option::Option::None) => {
+                ::std::option::Option::Some(#fallback)
+            }
+            ::std::result::Result::Err(e) => {
+                partial.push_error(e);
+                ::std::option::Option::Some(#fallback)
+            }
+        }
+    )
+}
+
+/// the value for a field with an error as an `Option` eg `fallback` or the value without any attributes
+fn error_fallback(def: &Path, f: &Field, fallback: Option<Expr>) -> Expr {
+    match fallback {
+        Some(fallback) => parse_quote!(::std::option::Option::Some(#fallback)),
+        None if is_repeated(&f.ty) => parse_quote!(#def.get_all_values::<>(&[]).ok()),
+        None => parse_quote!(#def.get_value::<>(&[]).ok()),
+    }
+}
+
+/// construct a struct or variant with the value for every field as an `Option`, `None` if a value is missing
+///
+/// Skipped fields are filled by `Default::default()` and other missing fields by their `default` or the field of `default`,
+/// the default value of the whole struct.
+fn construct(
+    path: &Path,
+    fields: &Fields,
+    default: Option<Expr>,
+    value: impl Fn(&Field, Ident, Option<Expr>) -> Expr,
+) -> Expr {
+    let mut bindings = Vec::new();
+    let mut stmts = Vec::<Stmt>::new();
+    let values = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| -> Expr {
+            if is_skipped(f) {
+                return parse_quote!(::core::default::Default::default());
+            }
+
+            let fallback = default_expr(&f.attrs).or_else(|| {
+                default.as_ref().map(|_| match &f.ident {
+                    Some(ident) => parse_quote!(default.#ident),
+                    None => {
+                        let index = Index::from(i);
+                        parse_quote!(default.#index)
+                    }
+                })
+            });
+            let binding = format_ident!("__field{}", i);
+            let value = value(f, field_ident(f, i), fallback);
+            stmts.push(parse_quote!(let #binding = #value;));
+            bindings.push(binding.clone());
+            parse_quote!(#binding)
+        })
+        .collect::<Vec<_>>();
+
+    let value: Expr = match fields {
+        Fields::Named(named) => {
+            let values = values
+                .iter()
+                .zip(
+                    named
+                        .named
+                        .iter()
+                        .map(|f| f.ident.as_ref().cloned().unwrap()),
+                )
+                .map(|(value, ident)| -> FieldValue { parse_quote!(#ident: #value) });
+
+            parse_quote!(
+                #path {
+                    #(#values),*
+                }
+            )
+        }
+        Fields::Unnamed(_) => parse_quote!(#path (#(#values),*)),
+        Fields::Unit => parse_quote!(#path),
+    };
+
+    let default: Option<Stmt> = default.map(|default| {
+        parse_quote!(
+            #[allow(unused_variables)]
+            let default: Self = #default;
+        )
+    });
+    parse_quote!({
+        #default
+        #(#stmts)*
+        #[allow(unreachable_patterns)]
+        match (#(#bindings,)*) {
+            (#(::std::option::Option::Some(#bindings),)*) => ::std::option::Option::Some(#value),
+            _ => ::std::option::Option::None,
+        }
+    })
+}
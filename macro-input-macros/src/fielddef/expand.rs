@@ -1,9 +1,19 @@
-use crate::{field_name, mod_name, DEFAULT_VALUE_FIELD, RENAME_FIELD};
-use heck::SnekCase;
+use crate::{
+    case::Case, default_expr, doc, field_ident, field_name, field_positions, groups,
+    has_nested_fields, input_path, is_flattened, is_newtype, is_optional, is_repeated, is_skipped,
+    keyed_fields, mod_name, rename_all, required_if, type_name, variant_mod_name, variant_name,
+    ALIAS_FIELD, AT_LEAST_ONE_OF_FIELD, CONFLICTS_WITH_FIELD, DEFAULT_VALUE_FIELD,
+    DUPLICATES_FIELD, EXACTLY_ONE_OF_FIELD, EXAMPLE_FIELD, HIDDEN_FIELD, REQUIRES_FIELD,
+};
 use macro_compose::{Collector, Context, Expand};
-use macro_input_core::DefaultValue;
+use macro_input_core::{DefaultValue, Duplicates, Flag, Types, Value};
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned, ToTokens};
 use std::convert::TryFrom;
-use syn::{parse_quote, Data, DeriveInput, Expr, Field, ItemConst, ItemMod, Type};
+use syn::{
+    parse_quote, spanned::Spanned, Attribute, Data, DeriveInput, Expr, Field, Fields, Item,
+    ItemConst, ItemMod, Result, Type,
+};
 
 pub struct ConstFields;
 
@@ -11,72 +21,357 @@ impl Expand<DeriveInput> for ConstFields {
     type Output = ItemMod;
 
     fn expand(&self, input: &DeriveInput, c: &mut Collector) -> Option<Self::Output> {
-        let path = RENAME_FIELD
-            .get_value::<Option<String>>(&input.attrs)
-            .unwrap()
-            .unwrap_or_else(|| input.ident.to_string().to_snek_case());
+        match const_fields_mod(input, c) {
+            Ok(item) => Some(item),
+            Err(e) => {
+                c.error(e);
+                None
+            }
+        }
+    }
+}
 
-        let const_field_expand = ConstFieldExpand { path };
+/// create the module with the [`Def`](macro_input_core::Def)s of the fields or variants of the input
+fn const_fields_mod(input: &DeriveInput, c: &mut Collector) -> Result<ItemMod> {
+    let path = input_path(input);
+    let case = rename_all(&input.attrs);
+    let defaulted = default_expr(&input.attrs).is_some();
+    let path_aliases = ALIAS_FIELD.get_all_values::<String>(&input.attrs)?;
 
-        let fields = match &input.data {
-            Data::Struct(s) => &s.fields,
-            _ => unreachable!(),
-        };
+    // flattened fields refer to the `Defs` of types in the module of the input
+    let imports: Option<Item> = if keyed_fields(input).into_iter().any(is_flattened) {
+        Some(parse_quote!(
+            use super::*;
+        ))
+    } else {
+        None
+    };
+
+    let mod_ident = mod_name(input);
+    match &input.data {
+        Data::Struct(s) => {
+            let items = const_fields(
+                &s.fields,
+                &input.attrs,
+                path,
+                &path_aliases,
+                case,
+                defaulted,
+                c,
+            )?;
+            Ok(parse_quote!(
+                mod #mod_ident {
+                    #imports
+                    #(#items)*
+                }
+            ))
+        }
+        Data::Enum(e) => {
+            let const_variants = e
+                .variants
+                .iter()
+                .map(|v| -> Result<ItemConst> {
+                    let (name, ident) = variant_name(v, case);
+                    let value = if matches!(v.fields, Fields::Unit) {
+                        DefaultValue::Flag
+                    } else {
+                        DefaultValue::Any(None)
+                    };
+                    let def = with_aliases(
+                        parse_quote!(::macro_input::Def::new(#path, #name, false, #value)),
+                        &ALIAS_FIELD.get_all_values(&v.attrs)?,
+                        &path_aliases,
+                    );
+                    let ty = match &v.fields {
+                        Fields::Unnamed(fields) if is_newtype(v) => Some(&fields.unnamed[0].ty),
+                        _ => None,
+                    };
+                    let def = with_help(def, &v.attrs, ty)?;
+                    Ok(parse_quote!(
+                        pub const #ident: ::macro_input::Def = #def;
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let variant_mods = e
+                .variants
+                .iter()
+                .filter(|v| has_nested_fields(v))
+                .map(|v| -> Result<ItemMod> {
+                    let (name, _) = variant_name(v, case);
+                    let items =
+                        const_fields(&v.fields, &[], name, &[], rename_all(&v.attrs), false, c)?;
+                    let mod_ident = variant_mod_name(v);
+                    Ok(parse_quote!(
+                        pub mod #mod_ident {
+                            #imports
+                            #(#items)*
+                        }
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let variant_refs = e.variants.iter().map(|v| -> Expr {
+                let (_, ident) = variant_name(v, case);
+                parse_quote!(#ident)
+            });
+
+            Ok(parse_quote!(
+                mod #mod_ident {
+                    #imports
+                    #(#const_variants)*
+
+                    const FIELDS: &[&::macro_input::Def] = &[#(&#variant_refs),*];
+                    pub const FIELD_DEFS: ::macro_input::Defs = ::macro_input::Defs::new(FIELDS);
 
-        let const_fields = fields.iter().map(|f| {
+                    #(#variant_mods)*
+                }
+            ))
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// create the [`Def`](macro_input_core::Def)s and the [`Defs`](macro_input_core::Defs) for some fields
+///
+/// `attrs` are the attributes with the constraints between the fields eg `#[macro_input(exactly_one_of("a", "b"))]`.
+fn const_fields(
+    fields: &Fields,
+    attrs: &[Attribute],
+    path: String,
+    path_aliases: &[String],
+    case: Option<Case>,
+    defaulted: bool,
+    c: &mut Collector,
+) -> Result<Vec<Item>> {
+    // skipped fields don't have a `Def`
+    let fields = fields
+        .iter()
+        .zip(field_positions(fields))
+        .enumerate()
+        .filter(|(_, (f, _))| !is_skipped(f))
+        .collect::<Vec<_>>();
+
+    let const_fields = fields
+        .iter()
+        .filter_map(|&(index, (f, position))| {
+            let const_field_expand = ConstFieldExpand {
+                path: &path,
+                path_aliases,
+                case,
+                defaulted,
+                index,
+                position,
+            };
             let mut subcontext = Context::new_by_ref(c, f);
             subcontext.capture(&const_field_expand)
-        });
+        })
+        .collect::<Vec<_>>();
 
-        let field_refs = fields.iter().map(|f| -> Expr {
-            let (_, ident) = field_name(f);
-            parse_quote!(#ident)
-        });
+    let field_refs = fields.iter().map(|&(i, (f, _))| -> Expr {
+        let ident = field_ident(f, i);
+        parse_quote!(#ident)
+    });
 
-        let mod_ident = mod_name(input);
-        Some(parse_quote!(
-            mod #mod_ident {
-                #(#const_fields)*
+    // flattened fields may not accept the keys of any other field
+    let key_checks = fields
+        .iter()
+        .enumerate()
+        .flat_map(|(n, &(i, (f, _)))| {
+            fields[n + 1..]
+                .iter()
+                .enumerate()
+                .filter(move |(_, (_, (other, _)))| is_flattened(f) || is_flattened(other))
+                .map(move |(m, &(j, (other, _)))| -> Item {
+                    let m = n + 1 + m;
+                    let message = format!(
+                        "the fields {} and {} accept the same key",
+                        field_name(f, i, None),
+                        field_name(other, j, None)
+                    );
+                    let span = if is_flattened(f) {
+                        f.span()
+                    } else {
+                        other.span()
+                    };
+                    let check = quote_spanned!(span=>
+                        ::std::assert!(!FIELDS[#n].shares_key(FIELDS[#m]), #message)
+                    );
+                    parse_quote!(
+                        const _: () = #check;
+                    )
+                })
+        })
+        .collect::<Vec<_>>();
 
-                const FIELDS: &[&::macro_input::Def] = &[#(&#field_refs),*];
-                pub const FIELD_DEFS: ::macro_input::Defs = ::macro_input::Defs::new(FIELDS);
-            }
-        ))
+    let mut field_defs: Expr = parse_quote!(::macro_input::Defs::new(FIELDS));
+    let exactly_one_of = groups(&EXACTLY_ONE_OF_FIELD, attrs)?;
+    if !exactly_one_of.is_empty() {
+        let groups = exactly_one_of.iter().map(|group| quote!(&[#(#group),*]));
+        field_defs = parse_quote!(#field_defs.with_exactly_one_of(&[#(#groups),*]));
+    }
+    let at_least_one_of = groups(&AT_LEAST_ONE_OF_FIELD, attrs)?;
+    if !at_least_one_of.is_empty() {
+        let groups = at_least_one_of.iter().map(|group| quote!(&[#(#group),*]));
+        field_defs = parse_quote!(#field_defs.with_at_least_one_of(&[#(#groups),*]));
     }
+
+    Ok(const_fields
+        .into_iter()
+        .map(Item::Const)
+        .chain(key_checks)
+        .chain(vec![
+            parse_quote!(const FIELDS: &[&::macro_input::Def] = &[#(&#field_refs),*];),
+            parse_quote!(
+                pub const FIELD_DEFS: ::macro_input::Defs = #field_defs;
+            ),
+        ])
+        .collect())
 }
 
-struct ConstFieldExpand {
-    path: String,
+struct ConstFieldExpand<'a> {
+    path: &'a str,
+    path_aliases: &'a [String],
+    case: Option<Case>,
+    /// whether missing fields are taken from the default value of the struct
+    defaulted: bool,
+    index: usize,
+    position: Option<usize>,
 }
 
-impl Expand<Field> for ConstFieldExpand {
+impl Expand<Field> for ConstFieldExpand<'_> {
     type Output = ItemConst;
 
-    fn expand(&self, f: &Field, _: &mut Collector) -> Option<Self::Output> {
-        fn is_optional(f: &Field) -> bool {
-            if let Type::Path(tp) = &f.ty {
-                tp.path.segments.len() == 1 && tp.path.segments.first().unwrap().ident == "Option"
-            } else {
-                false
+    fn expand(&self, f: &Field, c: &mut Collector) -> Option<Self::Output> {
+        match self.const_field(f) {
+            Ok(item) => Some(item),
+            Err(e) => {
+                c.error(e);
+                None
             }
         }
+    }
+}
 
-        let (name, ident) = field_name(f);
+impl ConstFieldExpand<'_> {
+    fn const_field(&self, f: &Field) -> Result<ItemConst> {
+        let name = field_name(f, self.index, self.case);
+        let ident = field_ident(f, self.index);
 
-        let default_value = DEFAULT_VALUE_FIELD.get_lit(&f.attrs).unwrap();
-        let value = DefaultValue::from_lit(
-            macro_input_core::Type::try_from(&f.ty).unwrap(),
-            default_value.clone(),
-        )
-        .unwrap();
+        if is_flattened(f) {
+            let path = self.path;
+            let ty = &f.ty;
+            let def = with_aliases(
+                parse_quote!(
+                    ::macro_input::Def::new(#path, #name, false, ::macro_input::DefaultValue::Any(None))
+                        .with_flattened(&<#ty>::FIELD_DEFS)
+                ),
+                &[],
+                self.path_aliases,
+            );
+            let def = with_help(def, &f.attrs, None)?;
+            return Ok(parse_quote!(
+                pub const #ident: ::macro_input::Def = #def;
+            ));
+        }
 
-        let optional = is_optional(f) || default_value.is_some();
+        let default_value = DEFAULT_VALUE_FIELD.get_value::<Option<Value>>(&f.attrs)?;
+        let ty = macro_input_core::Type::try_from(&f.ty).ok();
+        let value: TokenStream = match (ty, &default_value) {
+            (Some(ty), Some(Value::Lit(lit))) => {
+                DefaultValue::from_lit(ty, Some(lit.clone()))?.into_token_stream()
+            }
+            (Some(ty), _) => DefaultValue::from_lit(ty, None)?.into_token_stream(),
+            // types that aren't known to macro-input check their default value when parsing
+            (None, Some(value)) => quote!(::macro_input::DefaultValue::AnyValue(
+                ::std::option::Option::Some(|| ::syn::parse_quote!(#value))
+            )),
+            (None, None) => DefaultValue::Any(None).into_token_stream(),
+        };
+
+        let optional = is_optional(&f.ty)
+            || is_repeated(&f.ty)
+            || ty.map_or(false, |ty| ty.optional || matches!(ty.ty, Types::Bool))
+            || default_value.is_some()
+            || default_expr(&f.attrs).is_some()
+            || self.defaulted;
         let required = !optional;
 
-        let path = &self.path;
-        Some(parse_quote!(
-            pub const #ident: ::macro_input::Def =
-            ::macro_input::Def::new(#path, #name, #required, #value);
+        let path = self.path;
+        let def: Expr = parse_quote!(::macro_input::Def::new(#path, #name, #required, #value));
+        let def = match self.position {
+            Some(index) => parse_quote!(#def.with_position(#index)),
+            None => def,
+        };
+        let def = with_aliases(
+            def,
+            &ALIAS_FIELD.get_all_values(&f.attrs)?,
+            self.path_aliases,
+        );
+        let def = with_help(def, &f.attrs, Some(&f.ty))?;
+        let def: Expr = match DUPLICATES_FIELD.get_value::<Option<Duplicates>>(&f.attrs)? {
+            Some(duplicates) => parse_quote!(#def.with_duplicates(#duplicates)),
+            // repeated fields collect the values of all occurrences
+            None if is_repeated(&f.ty) => {
+                parse_quote!(#def.with_duplicates(::macro_input::Duplicates::Merge))
+            }
+            None => def,
+        };
+        let def = with_constraints(def, &f.attrs)?;
+
+        Ok(parse_quote!(
+            pub const #ident: ::macro_input::Def = #def;
         ))
     }
 }
+
+/// add the aliases of a field or variant and the aliases of its path to a [`Def`](macro_input_core::Def)
+fn with_aliases(mut def: Expr, aliases: &[String], path_aliases: &[String]) -> Expr {
+    if !aliases.is_empty() {
+        def = parse_quote!(#def.with_aliases(&[#(#aliases),*]));
+    }
+    if !path_aliases.is_empty() {
+        def = parse_quote!(#def.with_path_aliases(&[#(#path_aliases),*]));
+    }
+    def
+}
+
+/// add the constraints between a field and other fields to a [`Def`](macro_input_core::Def)
+fn with_constraints(mut def: Expr, attrs: &[Attribute]) -> Result<Expr> {
+    let conflicts_with = CONFLICTS_WITH_FIELD.get_all_values::<String>(attrs)?;
+    if !conflicts_with.is_empty() {
+        def = parse_quote!(#def.with_conflicts_with(&[#(#conflicts_with),*]));
+    }
+    let requires = REQUIRES_FIELD.get_all_values::<String>(attrs)?;
+    if !requires.is_empty() {
+        def = parse_quote!(#def.with_requires(&[#(#requires),*]));
+    }
+    let required_if = required_if(attrs)?;
+    if !required_if.is_empty() {
+        let conditions = required_if
+            .iter()
+            .map(|(name, value)| quote!((#name, #value)));
+        def = parse_quote!(#def.with_required_if(&[#(#conditions),*]));
+    }
+    Ok(def)
+}
+
+/// add the doc comment, the name of the type and the examples of a field or variant to a [`Def`](macro_input_core::Def)
+fn with_help(mut def: Expr, attrs: &[Attribute], ty: Option<&Type>) -> Result<Expr> {
+    let doc = doc(attrs);
+    if !doc.is_empty() {
+        def = parse_quote!(#def.with_doc(#doc));
+    }
+    if let Some(ty) = ty {
+        let type_name = type_name(ty);
+        def = parse_quote!(#def.with_type_name(#type_name));
+    }
+    if HIDDEN_FIELD.get_value::<Flag>(attrs)?.is_set() {
+        def = parse_quote!(#def.with_hidden(true));
+    }
+    let examples = EXAMPLE_FIELD.get_all_values::<String>(attrs)?;
+    if !examples.is_empty() {
+        def = parse_quote!(#def.with_examples(&[#(#examples),*]));
+    }
+    Ok(def)
+}
@@ -1,45 +1,254 @@
-use crate::{DEFAULT_VALUE_FIELD, RENAME_FIELD};
+use crate::{
+    all_fields, case::Case, field_name, field_positions, groups, has_nested_fields, is_emitted,
+    is_flattened, is_optional, is_repeated, is_skipped, keyed_fields, rename_all, required_if,
+    ALIAS_FIELD, AT_LEAST_ONE_OF_FIELD, CONFLICTS_WITH_FIELD, DEFAULT_FIELD, DEFAULT_VALUE_FIELD,
+    DUPLICATES_FIELD, EXACTLY_ONE_OF_FIELD, EXAMPLE_FIELD, FLATTEN_FIELD, HIDDEN_FIELD,
+    IDENT_FIELD, NON_EMPTY_FIELD, ONE_OF_FIELD, POSITIONAL_FIELD, RANGE_FIELD, RENAME_FIELD,
+    REQUIRED_IF_FIELD, REQUIRES_FIELD, SKIP_FIELD, VALIDATE_FIELD,
+};
 use macro_compose::{Collector, Context, Lint};
-use proc_macro2::Ident;
+use macro_input_core::{validate, Def, Duplicates, Meta, Spanned, Value};
 use std::convert::TryFrom;
-use syn::{parse_str, Data, DeriveInput, Error, Meta};
+use syn::{Attribute, Data, DeriveInput, Error, Expr, Fields, Path, Result};
 
 pub struct FieldType;
 
 impl Lint<DeriveInput> for FieldType {
     fn lint(&self, input: &DeriveInput, c: &mut Collector) {
-        if let Data::Struct(s) = &input.data {
-            for field in s.fields.iter() {
-                let ty = macro_input_core::Type::try_from(&field.ty);
-                match ty {
-                    Ok(mut ty) => {
-                        let default_value_attribute =
-                            DEFAULT_VALUE_FIELD.get_meta(&field.attrs).unwrap();
-
-                        if ty.optional {
-                            if let Some(attr) = &default_value_attribute {
-                                c.error(Error::new_spanned(
-                                    attr,
-                                    "optional fields can't have a default value",
-                                ));
-                            }
-                        }
+        for field in keyed_fields(input) {
+            // invalid options eg duplicate keys are reported by `input::STRUCT_LINT`
+            let default_value_attribute = match DEFAULT_VALUE_FIELD.get_meta(&field.attrs) {
+                Ok(meta) => meta,
+                Err(_) => continue,
+            };
+
+            if is_optional(&field.ty) {
+                if let Some(attr) = &default_value_attribute {
+                    c.error(Error::new_spanned(
+                        attr,
+                        "optional fields can't have a default value",
+                    ));
+                }
+            }
+
+            // other types check their default value when parsing
+            if let Ok(mut ty) = macro_input_core::Type::try_from(&field.ty) {
+                // defaults are optional
+                ty.optional = true;
+
+                let default_value = default_value_attribute.and_then(|meta| match meta {
+                    Meta::NameValue(mnv) => Some(mnv.value),
+                    _ => None,
+                });
+                let default_value = match &default_value {
+                    Some(Value::Lit(lit)) => Some(lit),
+                    Some(value) => {
+                        c.error(Error::new_spanned(value, "expected literal"));
+                        continue;
+                    }
+                    None => None,
+                };
+
+                let mut subcontext = Context::new_by_ref(c, &default_value);
+                subcontext.lint(&ty);
+            }
+        }
+    }
+}
+
+pub struct DuplicatePolicy;
+
+impl Lint<DeriveInput> for DuplicatePolicy {
+    fn lint(&self, input: &DeriveInput, c: &mut Collector) {
+        for field in keyed_fields(input) {
+            if let Err(e) = DUPLICATES_FIELD.get_value::<Option<Duplicates>>(&field.attrs) {
+                c.error(e);
+            }
+        }
+    }
+}
+
+pub struct EmitOrder;
+
+impl Lint<DeriveInput> for EmitOrder {
+    fn lint(&self, input: &DeriveInput, c: &mut Collector) {
+        if !is_emitted(input) {
+            return;
+        }
 
-                        // defaults are optional
-                        ty.optional = true;
+        let fields = match &input.data {
+            Data::Struct(s) => vec![&s.fields],
+            Data::Enum(e) => e
+                .variants
+                .iter()
+                .filter(|v| has_nested_fields(v))
+                .map(|v| &v.fields)
+                .collect(),
+            Data::Union(_) => Vec::new(),
+        };
+        for fields in fields {
+            lint_emit_order(fields, c);
+        }
+    }
+}
 
-                        let default_value = default_value_attribute.and_then(|meta| match meta {
-                            Meta::NameValue(mnv) => Some(mnv.lit),
-                            _ => None,
-                        });
-                        let default_value = default_value.as_ref();
+/// once a leading value is omitted the following ones have to be emitted by their keys
+fn lint_emit_order(fields: &Fields, c: &mut Collector) {
+    let mut omittable = false;
+    for (f, _) in fields
+        .iter()
+        .zip(field_positions(fields))
+        .filter(|(_, position)| position.is_some())
+    {
+        let renamed = matches!(RENAME_FIELD.get_meta(&f.attrs), Ok(Some(_)));
+        if omittable && f.ident.is_none() && !renamed {
+            c.error(Error::new_spanned(
+                f,
+                "this field can't be emitted after an omitted leading value because it has no key, rename it or add `#[macro_input(emit = false)]` to the input",
+            ));
+        }
+
+        omittable |= is_optional(&f.ty)
+            || is_repeated(&f.ty)
+            || matches!(DEFAULT_VALUE_FIELD.get_meta(&f.attrs), Ok(Some(_)));
+    }
+}
 
-                        let mut subcontext = Context::new_by_ref(c, &default_value);
-                        subcontext.lint(&ty);
+pub struct Validators;
+
+impl Lint<DeriveInput> for Validators {
+    fn lint(&self, input: &DeriveInput, c: &mut Collector) {
+        if let Err(e) = VALIDATE_FIELD.get_all_values::<Path>(&input.attrs) {
+            c.error(e);
+        }
+
+        for field in all_fields(input) {
+            if let Err(e) = lint_validators(&field.attrs) {
+                c.error(e);
+            }
+        }
+    }
+}
+
+/// `validate` is the path of a function, `range` a range expression and `one_of` a list of values
+fn lint_validators(attrs: &[Attribute]) -> Result<()> {
+    VALIDATE_FIELD.get_all_values::<Path>(attrs)?;
+    if let Some(range) = RANGE_FIELD.get_value::<Option<Expr>>(attrs)? {
+        if !matches!(range, Expr::Range(_)) {
+            return Err(Error::new_spanned(range, "expected a range eg \"1..=10\""));
+        }
+    }
+    ONE_OF_FIELD.get_all_values::<Value>(attrs)?;
+    Ok(())
+}
+
+pub struct Constraints;
+
+impl Lint<DeriveInput> for Constraints {
+    fn lint(&self, input: &DeriveInput, c: &mut Collector) {
+        match &input.data {
+            Data::Struct(s) => {
+                lint_constraints(&s.fields, rename_all(&input.attrs), &input.attrs, c);
+            }
+            Data::Enum(e) => {
+                for def in [&EXACTLY_ONE_OF_FIELD, &AT_LEAST_ONE_OF_FIELD].iter() {
+                    if let Ok(Some(meta)) = def.get_meta(&input.attrs) {
+                        c.error(Error::new_spanned(
+                            meta,
+                            format!("only structs can have {}", def.name),
+                        ));
                     }
-                    Err(e) => c.error(e),
+                }
+                for v in e.variants.iter() {
+                    lint_constraints(&v.fields, rename_all(&v.attrs), &[], c);
                 }
             }
+            Data::Union(_) => {}
+        }
+    }
+}
+
+/// the names in the constraints between fields have to be the keys of other fields
+fn lint_constraints(fields: &Fields, case: Option<Case>, attrs: &[Attribute], c: &mut Collector) {
+    let keys = fields
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| !is_skipped(f) && !is_flattened(f))
+        .map(|(i, f)| field_name(f, i, case))
+        .collect::<Vec<_>>();
+
+    for def in [&EXACTLY_ONE_OF_FIELD, &AT_LEAST_ONE_OF_FIELD].iter() {
+        match groups(def, attrs) {
+            Ok(groups) => check_names(&keys, def, attrs, groups.into_iter().flatten().collect(), c),
+            Err(e) => c.error(e),
+        }
+    }
+
+    for f in fields.iter() {
+        for def in [&CONFLICTS_WITH_FIELD, &REQUIRES_FIELD].iter() {
+            match def.get_all_values::<String>(&f.attrs) {
+                Ok(names) => check_names(&keys, def, &f.attrs, names, c),
+                Err(e) => c.error(e),
+            }
+        }
+        match required_if(&f.attrs) {
+            Ok(conditions) => {
+                let names = conditions
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>();
+                lint_required_if_defaults(fields, case, attrs, &f.attrs, &names, c);
+                check_names(&keys, &REQUIRED_IF_FIELD, &f.attrs, names, c);
+            }
+            Err(e) => c.error(e),
+        }
+    }
+}
+
+/// conditions can only be checked against default values that are known before parsing eg `default_value`
+fn lint_required_if_defaults(
+    fields: &Fields,
+    case: Option<Case>,
+    attrs: &[Attribute],
+    field_attrs: &[Attribute],
+    names: &[String],
+    c: &mut Collector,
+) {
+    let input_default = matches!(DEFAULT_FIELD.get_meta(attrs), Ok(Some(_)));
+    for (i, other) in fields.iter().enumerate() {
+        let name = field_name(other, i, case);
+        if !names.contains(&name) {
+            continue;
+        }
+        if input_default || matches!(DEFAULT_FIELD.get_meta(&other.attrs), Ok(Some(_))) {
+            if let Ok(meta) = REQUIRED_IF_FIELD.get_meta(field_attrs) {
+                c.error(Error::new_spanned(
+                    meta,
+                    format!(
+                        "required_if can't depend on {} because its default is only known after parsing, use default_value instead",
+                        name
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+fn check_names(
+    keys: &[String],
+    def: &Def,
+    attrs: &[Attribute],
+    names: Vec<String>,
+    c: &mut Collector,
+) {
+    for name in names {
+        if !keys.contains(&name) {
+            if let Ok(meta) = def.get_meta(attrs) {
+                c.error(Error::new_spanned(
+                    meta,
+                    format!("unknown field in {}: {}", def.name, name),
+                ));
+            }
         }
     }
 }
@@ -48,24 +257,129 @@ pub struct Name;
 
 impl Lint<DeriveInput> for Name {
     fn lint(&self, input: &DeriveInput, c: &mut Collector) {
-        if let Data::Struct(s) = &input.data {
-            for field in s.fields.iter() {
-                if let Some(name) = RENAME_FIELD
-                    .get_value::<Option<String>>(&field.attrs)
-                    .unwrap()
-                {
-                    if let Err(e) = parse_str::<Ident>(&name) {
-                        let meta = RENAME_FIELD.get_lit(&field.attrs).unwrap();
-                        let e = Error::new_spanned(meta, e);
-                        c.error(e);
+        for field in keyed_fields(input) {
+            let keys = RENAME_FIELD
+                .get_value::<Option<Spanned<String>>>(&field.attrs)
+                .map(|rename| rename.into_iter().collect::<Vec<_>>())
+                .and_then(|mut keys| {
+                    keys.extend(ALIAS_FIELD.get_all_values::<Spanned<String>>(&field.attrs)?);
+                    Ok(keys)
+                });
+            match keys {
+                Ok(keys) => {
+                    for key in keys {
+                        if let Err(e) = validate::key(&*key, key.span()) {
+                            c.error(e);
+                        }
                     }
-                } else if field.ident.is_none() {
-                    c.error(Error::new_spanned(
-                        field,
-                        "add #[macro_input(name = $name)] for fields on unnamed structs",
-                    ));
                 }
+                Err(e) => c.error(e),
             }
         }
     }
 }
+
+pub struct Conflicts;
+
+impl Lint<DeriveInput> for Conflicts {
+    fn lint(&self, input: &DeriveInput, c: &mut Collector) {
+        // flattened and skipped fields don't have a key of their own
+        let conflicts: &[(&Def, &[&Def])] = &[
+            (
+                &FLATTEN_FIELD,
+                &[
+                    &RENAME_FIELD,
+                    &ALIAS_FIELD,
+                    &DEFAULT_VALUE_FIELD,
+                    &DEFAULT_FIELD,
+                    &POSITIONAL_FIELD,
+                    &SKIP_FIELD,
+                    &EXAMPLE_FIELD,
+                    &DUPLICATES_FIELD,
+                    &VALIDATE_FIELD,
+                    &RANGE_FIELD,
+                    &ONE_OF_FIELD,
+                    &NON_EMPTY_FIELD,
+                    &IDENT_FIELD,
+                    &CONFLICTS_WITH_FIELD,
+                    &REQUIRES_FIELD,
+                    &REQUIRED_IF_FIELD,
+                ],
+            ),
+            (
+                &SKIP_FIELD,
+                &[
+                    &RENAME_FIELD,
+                    &ALIAS_FIELD,
+                    &DEFAULT_VALUE_FIELD,
+                    &DEFAULT_FIELD,
+                    &POSITIONAL_FIELD,
+                    &HIDDEN_FIELD,
+                    &EXAMPLE_FIELD,
+                    &DUPLICATES_FIELD,
+                    &VALIDATE_FIELD,
+                    &RANGE_FIELD,
+                    &ONE_OF_FIELD,
+                    &NON_EMPTY_FIELD,
+                    &IDENT_FIELD,
+                    &CONFLICTS_WITH_FIELD,
+                    &REQUIRES_FIELD,
+                    &REQUIRED_IF_FIELD,
+                ],
+            ),
+            (&DEFAULT_FIELD, &[&DEFAULT_VALUE_FIELD]),
+        ];
+
+        for field in all_fields(input) {
+            for (def, others) in conflicts.iter() {
+                if !matches!(def.get_meta(&field.attrs), Ok(Some(_))) {
+                    continue;
+                }
+
+                for other in others.iter() {
+                    if let Ok(Some(meta)) = other.get_meta(&field.attrs) {
+                        c.error(Error::new_spanned(
+                            meta,
+                            format!("{} can't be combined with {}", def.name, other.name),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub struct Defaults;
+
+impl Lint<DeriveInput> for Defaults {
+    fn lint(&self, input: &DeriveInput, c: &mut Collector) {
+        if let Err(e) = lint_default(&input.attrs) {
+            c.error(e);
+        }
+        if let (Data::Enum(_), Ok(Some(meta))) = (&input.data, DEFAULT_FIELD.get_meta(&input.attrs))
+        {
+            c.error(Error::new_spanned(
+                meta,
+                "only structs can have a default value",
+            ));
+        }
+
+        for field in all_fields(input) {
+            if let Err(e) = lint_default(&field.attrs) {
+                c.error(e);
+            }
+        }
+    }
+}
+
+/// `default` is either a flag or the path of a function
+fn lint_default(attrs: &[Attribute]) -> Result<()> {
+    match DEFAULT_FIELD.get_meta(attrs)? {
+        Some(Meta::List(list)) => Err(Error::new_spanned(
+            list,
+            "expected `default` or `default = \"path::to::fn\"`",
+        )),
+        Some(Meta::NameValue(_)) => DEFAULT_FIELD.get_value::<Path>(attrs).map(drop),
+        _ => Ok(()),
+    }
+}
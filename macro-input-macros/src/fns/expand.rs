@@ -1,6 +1,14 @@
-use crate::mod_name;
+use crate::{
+    bounded_generics, emit_generics, field_ident, field_positions, has_nested_fields, input_path,
+    is_emitted, is_flattened, is_newtype, is_repeated, is_skipped, mod_name, rename_all,
+    variant_mod_name, variant_name,
+};
 use macro_compose::{Collector, Expand};
-use syn::{parse_quote, DeriveInput, ItemImpl};
+use proc_macro2::Ident;
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, Arm, Data, DeriveInput, Expr, Field, Fields, Index, ItemImpl, Member, Path, Stmt,
+};
 
 pub struct Strip;
 
@@ -9,10 +17,14 @@ impl Expand<DeriveInput> for Strip {
 
     fn expand(&self, input: &DeriveInput, _: &mut Collector) -> Option<Self::Output> {
         let ident = &input.ident;
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
         let mod_name = mod_name(input);
 
         Some(parse_quote!(
-            impl #ident {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// the definitions of the fields
+                pub const FIELD_DEFS: ::macro_input::Defs<'static> = #mod_name::FIELD_DEFS;
+
                 /// strip away the fields from the attributes
                 pub fn strip(attrs: &mut ::std::vec::Vec<::syn::Attribute>) {
                     #mod_name :: FIELD_DEFS .strip(attrs);
@@ -21,3 +33,214 @@ impl Expand<DeriveInput> for Strip {
         ))
     }
 }
+
+pub struct Constructors;
+
+impl Expand<DeriveInput> for Constructors {
+    type Output = ItemImpl;
+
+    fn expand(&self, input: &DeriveInput, _: &mut Collector) -> Option<Self::Output> {
+        let ident = &input.ident;
+        let generics = bounded_generics(input);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let path = input_path(input);
+
+        Some(parse_quote!(
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// parse the arguments of an attribute macro or the input of a function-like macro eg `bar = 3, baz`
+                pub fn from_args(
+                    tokens: impl ::std::convert::Into<::proc_macro2::TokenStream>,
+                ) -> ::syn::Result<Self> {
+                    let nested = ::syn::parse::Parser::parse2(
+                        ::syn::punctuated::Punctuated::<::macro_input::NestedMeta, ::syn::Token![,]>::parse_terminated,
+                        tokens.into(),
+                    )?;
+                    Self::from_nested(&nested.into_iter().collect::<::std::vec::Vec<_>>())
+                }
+
+                /// parse already parsed arguments
+                pub fn from_nested(nested: &[::macro_input::NestedMeta]) -> ::syn::Result<Self> {
+                    Self::from_nested_spanned(nested, ::proc_macro2::Span::call_site())
+                }
+
+                /// parse already parsed arguments, errors for missing required fields point at `span` eg the arguments
+                pub fn from_nested_spanned(
+                    nested: &[::macro_input::NestedMeta],
+                    span: ::proc_macro2::Span,
+                ) -> ::syn::Result<Self> {
+                    let list = ::macro_input::MetaList {
+                        path: <::syn::Path as ::std::convert::From<_>>::from(::proc_macro2::Ident::new(#path, span)),
+                        paren_token: ::syn::token::Paren(span),
+                        nested: nested.iter().cloned().collect(),
+                    };
+                    Self::from_meta_partial_spanned(
+                        ::std::option::Option::Some(::macro_input::Meta::List(list)),
+                        span,
+                    )
+                    .into_result()
+                }
+            }
+        ))
+    }
+}
+
+pub struct Emit;
+
+impl Expand<DeriveInput> for Emit {
+    type Output = ItemImpl;
+
+    fn expand(&self, input: &DeriveInput, _: &mut Collector) -> Option<Self::Output> {
+        if !is_emitted(input) {
+            return None;
+        }
+
+        let ident = &input.ident;
+        let generics = emit_generics(input);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let path = format_ident!("{}", input_path(input));
+
+        let mod_name = mod_name(input);
+        let nested: Expr = match &input.data {
+            Data::Struct(s) => {
+                let stmts = emit_fields(&s.fields, |f, i| {
+                    let ident = field_ident(f, i);
+                    let member = member(f, i);
+                    (parse_quote!(#mod_name::#ident), parse_quote!(&self.#member))
+                });
+                parse_quote!({
+                    let mut nested = ::std::vec::Vec::new();
+                    #(#stmts)*
+                    nested
+                })
+            }
+            Data::Enum(e) => {
+                let case = rename_all(&input.attrs);
+                let arms = e.variants.iter().map(|v| -> Arm {
+                    let (_, def) = variant_name(v, case);
+                    let ident = &v.ident;
+                    let flag: Expr = parse_quote!(::macro_input::NestedMeta::Meta(
+                        ::macro_input::Meta::Path(#mod_name::#def.key())
+                    ));
+
+                    if is_newtype(v) {
+                        parse_quote!(
+                            Self::#ident(value) => #mod_name::#def
+                                .to_nested(value, false)
+                                .unwrap_or_else(|| #flag)
+                        )
+                    } else if has_nested_fields(v) {
+                        let variant_mod_name = variant_mod_name(v);
+                        let pats = v.fields.iter().enumerate().map(|(i, f)| {
+                            let member = member(f, i);
+                            if is_skipped(f) {
+                                quote!(#member: _)
+                            } else {
+                                let binding = binding(i);
+                                quote!(#member: #binding)
+                            }
+                        });
+                        let stmts = emit_fields(&v.fields, |f, i| {
+                            let ident = field_ident(f, i);
+                            let binding = binding(i);
+                            (
+                                parse_quote!(#mod_name::#variant_mod_name::#ident),
+                                parse_quote!(#binding),
+                            )
+                        });
+                        parse_quote!(
+                            Self::#ident { #(#pats),* } => {
+                                let mut nested = ::std::vec::Vec::new();
+                                #(#stmts)*
+                                ::macro_input::NestedMeta::Meta(::macro_input::Meta::List(
+                                    ::macro_input::MetaList {
+                                        path: #mod_name::#def.key(),
+                                        paren_token: ::std::default::Default::default(),
+                                        nested: nested.into_iter().collect(),
+                                    },
+                                ))
+                            }
+                        )
+                    } else {
+                        parse_quote!(Self::#ident => #flag)
+                    }
+                });
+                parse_quote!(::std::vec![match self {
+                    #(#arms,)*
+                }])
+            }
+            _ => unreachable!(),
+        };
+
+        Some(parse_quote!(
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// the reverse of parsing: the arguments for the input eg `foo(bar = 3, baz)`
+                ///
+                /// If `omit_defaults` is set arguments that have their default value are omitted.
+                pub fn to_meta_list(&self, omit_defaults: bool) -> ::macro_input::MetaList {
+                    let nested: ::std::vec::Vec<::macro_input::NestedMeta> = #nested;
+                    ::macro_input::MetaList {
+                        path: ::syn::parse_quote!(#path),
+                        paren_token: ::std::default::Default::default(),
+                        nested: nested.into_iter().collect(),
+                    }
+                }
+
+                /// emit the input as an attribute eg `#[foo(bar = 3, baz)]`
+                pub fn into_attribute(self) -> ::syn::Attribute {
+                    self.to_meta_list(false).into_attribute()
+                }
+            }
+        ))
+    }
+}
+
+/// the statements pushing the arguments for every field that isn't skipped to `nested`
+///
+/// `field` returns the path of the `Def` of a field and an expression referencing its value.
+fn emit_fields(fields: &Fields, field: impl Fn(&Field, usize) -> (Path, Expr)) -> Vec<Stmt> {
+    // leading values have to come first so positional fields are emitted before all others
+    let (positional, others): (Vec<_>, Vec<_>) = fields
+        .iter()
+        .enumerate()
+        .zip(field_positions(fields))
+        .filter(|((_, f), _)| !is_skipped(f))
+        .partition(|(_, position)| position.is_some());
+
+    let mut in_order = false;
+    let mut stmts: Vec<Stmt> = positional
+        .into_iter()
+        .chain(others)
+        .map(|((i, f), _)| -> Stmt {
+            let (def, value) = field(f, i);
+            if is_flattened(f) {
+                parse_quote!(nested.extend((#value).to_meta_list(omit_defaults).nested);)
+            } else if is_repeated(&f.ty) {
+                parse_quote!(
+                    nested.extend((#value).iter().filter_map(|value| #def.to_nested(value, false)));
+                )
+            } else {
+                in_order = true;
+                parse_quote!(
+                    nested.extend(#def.to_nested_in_order(#value, omit_defaults, &mut leading));
+                )
+            }
+        })
+        .collect();
+    if in_order {
+        stmts.insert(0, parse_quote!(let mut leading = true;));
+    }
+    stmts
+}
+
+/// the member of the `index`th field eg `bar` or `0`
+fn member(f: &Field, index: usize) -> Member {
+    match &f.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(index)),
+    }
+}
+
+/// the binding of the `index`th field of a variant
+fn binding(index: usize) -> Ident {
+    format_ident!("__field{}", index)
+}
@@ -0,0 +1,119 @@
+use crate::{variant_values, CASE_INSENSITIVE_FIELD};
+use macro_compose::{Collector, Expand};
+use syn::{parse_quote, Arm, Data, DeriveInput, Expr, ItemImpl};
+
+pub struct FromLit;
+
+impl Expand<DeriveInput> for FromLit {
+    type Output = ItemImpl;
+
+    fn expand(&self, input: &DeriveInput, c: &mut Collector) -> Option<Self::Output> {
+        let ident = &input.ident;
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+        let variants = match &input.data {
+            Data::Enum(e) => &e.variants,
+            _ => unreachable!(),
+        };
+
+        let arms = variants.iter().map(|v| -> Arm {
+            let values = variant_values(input, v);
+            let ident = &v.ident;
+            parse_quote!(#(#values)|* => ::std::result::Result::Ok(Self::#ident))
+        });
+
+        let expected = variants
+            .iter()
+            .flat_map(|v| variant_values(input, v))
+            .map(|value| format!("{:?}", value))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let expected = format!("expected one of: {}", expected);
+
+        let case_insensitive = match CASE_INSENSITIVE_FIELD.get_value::<Option<()>>(&input.attrs) {
+            Ok(flag) => flag.is_some(),
+            Err(e) => {
+                c.error(e);
+                return None;
+            }
+        };
+        let value: Expr = if case_insensitive {
+            parse_quote!(v.value().to_lowercase())
+        } else {
+            parse_quote!(v.value())
+        };
+
+        Some(parse_quote!(
+            impl #impl_generics ::macro_input::FromLit for #ident #ty_generics #where_clause {
+                fn from(lit: ::std::option::Option<::syn::Lit>) -> ::syn::Result<Self> {
+                    if let ::std::option::Option::Some(::syn::Lit::Str(v)) = &lit {
+                        match #value.as_str() {
+                            #(#arms,)*
+                            _ => ::std::result::Result::Err(::syn::Error::new_spanned(v, #expected)),
+                        }
+                    } else {
+                        ::std::result::Result::Err(::syn::Error::new_spanned(lit, "expected string"))
+                    }
+                }
+
+                fn from_value(value: ::macro_input::Value) -> ::syn::Result<Self> {
+                    // identifiers are accepted as well eg `mode = fast`
+                    if let ::macro_input::Value::Expr(::syn::Expr::Path(expr)) = &value {
+                        if let (::std::option::Option::None, ::std::option::Option::Some(ident)) =
+                            (&expr.qself, expr.path.get_ident())
+                        {
+                            let name = ::syn::ext::IdentExt::unraw(ident).to_string();
+                            let lit = ::syn::LitStr::new(&name, ident.span());
+                            return <Self as ::macro_input::FromLit>::from(
+                                ::std::option::Option::Some(::syn::Lit::Str(lit)),
+                            );
+                        }
+                    }
+                    match value {
+                        ::macro_input::Value::Lit(lit) => {
+                            <Self as ::macro_input::FromLit>::from(::std::option::Option::Some(lit))
+                        }
+                        value => ::std::result::Result::Err(::syn::Error::new_spanned(
+                            value,
+                            "expected string or identifier",
+                        )),
+                    }
+                }
+            }
+        ))
+    }
+}
+
+pub struct ToLit;
+
+impl Expand<DeriveInput> for ToLit {
+    type Output = ItemImpl;
+
+    fn expand(&self, input: &DeriveInput, _: &mut Collector) -> Option<Self::Output> {
+        let ident = &input.ident;
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+        let variants = match &input.data {
+            Data::Enum(e) => &e.variants,
+            _ => unreachable!(),
+        };
+
+        // the first string of a variant is its name
+        let arms = variants.iter().map(|v| -> Arm {
+            let value = &variant_values(input, v)[0];
+            let ident = &v.ident;
+            parse_quote!(Self::#ident => #value)
+        });
+
+        Some(parse_quote!(
+            impl #impl_generics ::macro_input::ToLit for #ident #ty_generics #where_clause {
+                fn to_value(&self) -> ::macro_input::Value {
+                    let value = match self {
+                        #(#arms,)*
+                    };
+                    ::macro_input::ToLit::to_value(&::std::borrow::ToOwned::to_owned(value))
+                }
+            }
+        ))
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{case::Case, variant_values, ALIAS_FIELD, CASE_INSENSITIVE_FIELD, RENAME_ALL_FIELD};
+use macro_compose::{Collector, Lint};
+use std::collections::HashMap;
+use syn::{Data, DeriveInput, Error, Fields};
+
+pub struct UnitVariants;
+
+impl Lint<DeriveInput> for UnitVariants {
+    fn lint(&self, input: &DeriveInput, c: &mut Collector) {
+        match &input.data {
+            Data::Enum(e) => {
+                for variant in e.variants.iter() {
+                    if !matches!(variant.fields, Fields::Unit) {
+                        c.error(Error::new_spanned(&variant.fields, "unexpected fields"));
+                    }
+                }
+            }
+            _ => c.error(Error::new_spanned(input, "expected an enum")),
+        }
+    }
+}
+
+pub struct Values;
+
+impl Lint<DeriveInput> for Values {
+    fn lint(&self, input: &DeriveInput, c: &mut Collector) {
+        if let Err(e) = CASE_INSENSITIVE_FIELD.get_value::<Option<()>>(&input.attrs) {
+            c.error(e);
+            return;
+        }
+        if let Err(e) = RENAME_ALL_FIELD.get_value::<Option<Case>>(&input.attrs) {
+            c.error(e);
+            return;
+        }
+
+        if let Data::Enum(e) = &input.data {
+            for variant in e.variants.iter() {
+                if let Err(e) = ALIAS_FIELD.get_all_values::<String>(&variant.attrs) {
+                    c.error(e);
+                    return;
+                }
+            }
+
+            let mut seen = HashMap::new();
+            for variant in e.variants.iter() {
+                for value in variant_values(input, variant) {
+                    if let Some(other) = seen.insert(value.clone(), &variant.ident) {
+                        c.error(Error::new_spanned(
+                            &variant.ident,
+                            format!("\"{}\" is already used by {}", value, other),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,17 +1,114 @@
-use macro_input_core::{Def, DefaultValue, Defs, StructLint};
+use macro_input_core::{Def, DefaultValue, Defs, Duplicates, StructLint};
 
 pub const RENAME_FIELD: Def = Def::new("macro_input", "rename", false, DefaultValue::Str(None));
+pub const RENAME_ALL_FIELD: Def =
+    Def::new("macro_input", "rename_all", false, DefaultValue::Str(None));
 pub const DEFAULT_VALUE_FIELD: Def = Def::new(
     "macro_input",
     "default_value",
     false,
     DefaultValue::Any(None),
 );
+pub const DEFAULT_FIELD: Def = Def::new("macro_input", "default", false, DefaultValue::Any(None));
+pub const SKIP_FIELD: Def = Def::new("macro_input", "skip", false, DefaultValue::Flag);
+pub const ALIAS_FIELD: Def = Def::new("macro_input", "alias", false, DefaultValue::Any(None))
+    .with_duplicates(Duplicates::Merge);
+pub const POSITIONAL_FIELD: Def = Def::new("macro_input", "positional", false, DefaultValue::Flag);
+pub const FLATTEN_FIELD: Def = Def::new("macro_input", "flatten", false, DefaultValue::Flag);
+pub const HIDDEN_FIELD: Def = Def::new("macro_input", "hidden", false, DefaultValue::Flag);
+pub const EXAMPLE_FIELD: Def = Def::new("macro_input", "example", false, DefaultValue::Any(None))
+    .with_duplicates(Duplicates::Merge);
+pub const DUPLICATES_FIELD: Def =
+    Def::new("macro_input", "duplicates", false, DefaultValue::Str(None));
+pub const EMIT_FIELD: Def = Def::new("macro_input", "emit", false, DefaultValue::Bool(Some(true)));
+pub const PARSE_FIELD: Def = Def::new("macro_input", "parse", false, DefaultValue::Flag);
+pub const ALLOW_UNKNOWN_FIELD: Def =
+    Def::new("macro_input", "allow_unknown", false, DefaultValue::Flag);
+pub const VALIDATE_FIELD: Def = Def::new("macro_input", "validate", false, DefaultValue::Str(None))
+    .with_duplicates(Duplicates::Merge);
+pub const RANGE_FIELD: Def = Def::new("macro_input", "range", false, DefaultValue::Str(None));
+pub const ONE_OF_FIELD: Def = Def::new("macro_input", "one_of", false, DefaultValue::Any(None));
+pub const NON_EMPTY_FIELD: Def = Def::new("macro_input", "non_empty", false, DefaultValue::Flag);
+pub const IDENT_FIELD: Def = Def::new("macro_input", "ident", false, DefaultValue::Flag);
+pub const CONFLICTS_WITH_FIELD: Def = Def::new(
+    "macro_input",
+    "conflicts_with",
+    false,
+    DefaultValue::Any(None),
+)
+.with_duplicates(Duplicates::Merge);
+pub const REQUIRES_FIELD: Def = Def::new("macro_input", "requires", false, DefaultValue::Any(None))
+    .with_duplicates(Duplicates::Merge);
+pub const REQUIRED_IF_FIELD: Def =
+    Def::new("macro_input", "required_if", false, DefaultValue::Any(None))
+        .with_duplicates(Duplicates::Merge);
+pub const EXACTLY_ONE_OF_FIELD: Def = Def::new(
+    "macro_input",
+    "exactly_one_of",
+    false,
+    DefaultValue::Any(None),
+)
+.with_duplicates(Duplicates::Merge);
+pub const AT_LEAST_ONE_OF_FIELD: Def = Def::new(
+    "macro_input",
+    "at_least_one_of",
+    false,
+    DefaultValue::Any(None),
+)
+.with_duplicates(Duplicates::Merge);
+pub const CASE_INSENSITIVE_FIELD: Def =
+    Def::new("macro_input", "case_insensitive", false, DefaultValue::Flag);
 
-const FIELDS_FIELDS: &[&Def] = &[&RENAME_FIELD, &DEFAULT_VALUE_FIELD];
+const FIELDS_FIELDS: &[&Def] = &[
+    &RENAME_FIELD,
+    &ALIAS_FIELD,
+    &DEFAULT_VALUE_FIELD,
+    &DEFAULT_FIELD,
+    &POSITIONAL_FIELD,
+    &FLATTEN_FIELD,
+    &SKIP_FIELD,
+    &HIDDEN_FIELD,
+    &EXAMPLE_FIELD,
+    &DUPLICATES_FIELD,
+    &VALIDATE_FIELD,
+    &RANGE_FIELD,
+    &ONE_OF_FIELD,
+    &NON_EMPTY_FIELD,
+    &IDENT_FIELD,
+    &CONFLICTS_WITH_FIELD,
+    &REQUIRES_FIELD,
+    &REQUIRED_IF_FIELD,
+];
 const FIELDS_FIELD_DEFS: Defs = Defs::new(FIELDS_FIELDS);
 
-const STRUCT_FIELDS: &[&Def] = &[&RENAME_FIELD];
+const STRUCT_FIELDS: &[&Def] = &[
+    &RENAME_FIELD,
+    &RENAME_ALL_FIELD,
+    &ALIAS_FIELD,
+    &DEFAULT_FIELD,
+    &EMIT_FIELD,
+    &PARSE_FIELD,
+    &ALLOW_UNKNOWN_FIELD,
+    &VALIDATE_FIELD,
+    &EXACTLY_ONE_OF_FIELD,
+    &AT_LEAST_ONE_OF_FIELD,
+];
 const STRUCT_FIELD_DEFS: Defs = Defs::new(STRUCT_FIELDS);
 
-pub const STRUCT_LINT: StructLint = StructLint::new(&STRUCT_FIELD_DEFS, &FIELDS_FIELD_DEFS);
+const VARIANT_FIELDS: &[&Def] = &[&RENAME_FIELD, &RENAME_ALL_FIELD, &ALIAS_FIELD];
+const VARIANT_FIELD_DEFS: Defs = Defs::new(VARIANT_FIELDS);
+
+pub const STRUCT_LINT: StructLint =
+    StructLint::new_with_variants(&STRUCT_FIELD_DEFS, &VARIANT_FIELD_DEFS, &FIELDS_FIELD_DEFS);
+
+const FROM_LIT_FIELDS: &[&Def] = &[&CASE_INSENSITIVE_FIELD, &RENAME_ALL_FIELD];
+const FROM_LIT_FIELD_DEFS: Defs = Defs::new(FROM_LIT_FIELDS);
+
+const FROM_LIT_VARIANT_FIELDS: &[&Def] = &[&RENAME_FIELD, &ALIAS_FIELD];
+const FROM_LIT_VARIANT_FIELD_DEFS: Defs = Defs::new(FROM_LIT_VARIANT_FIELDS);
+
+pub const FROM_LIT_LINT: StructLint = StructLint::new_with_variants(
+    &FROM_LIT_FIELD_DEFS,
+    &FROM_LIT_VARIANT_FIELD_DEFS,
+    Defs::empty(),
+);
@@ -1,30 +1,117 @@
+mod case;
 mod convert;
 mod fielddef;
 mod fns;
+mod fromlit;
 mod input;
 mod lint;
 
+use case::Case;
 use heck::{ShoutySnekCase, SnekCase};
-use input::{DEFAULT_VALUE_FIELD, RENAME_FIELD};
+use input::{
+    ALIAS_FIELD, ALLOW_UNKNOWN_FIELD, AT_LEAST_ONE_OF_FIELD, CASE_INSENSITIVE_FIELD,
+    CONFLICTS_WITH_FIELD, DEFAULT_FIELD, DEFAULT_VALUE_FIELD, DUPLICATES_FIELD, EMIT_FIELD,
+    EXACTLY_ONE_OF_FIELD, EXAMPLE_FIELD, FLATTEN_FIELD, HIDDEN_FIELD, IDENT_FIELD, NON_EMPTY_FIELD,
+    ONE_OF_FIELD, PARSE_FIELD, POSITIONAL_FIELD, RANGE_FIELD, RENAME_ALL_FIELD, RENAME_FIELD,
+    REQUIRED_IF_FIELD, REQUIRES_FIELD, SKIP_FIELD, VALIDATE_FIELD,
+};
 use macro_compose::{Collector, Context};
+use macro_input_core::{Def, Flag, FromMeta, Meta, MetaList, NestedMeta, Value};
 use proc_macro::TokenStream;
 use proc_macro2::Ident;
-use quote::format_ident;
-use syn::{DeriveInput, Field, LitStr};
+use quote::{format_ident, ToTokens};
+use syn::{
+    ext::IdentExt,
+    parse_quote,
+    spanned::Spanned,
+    visit::{self, Visit},
+    Attribute, Data, DeriveInput, Expr, Field, Fields, GenericArgument, Generics, Lit, LitStr,
+    Path, PathArguments, ReturnType, Stmt, Type, Variant,
+};
 
 #[proc_macro_derive(MacroInput, attributes(macro_input))]
-/// automatically derive `TryFrom<&[syn::Attribute]>` and `fn strip(attrs: &mut Vec<syn::Attribute>)`
+/// automatically derive `TryFrom<&[syn::Attribute]>`, `FromMeta` and `fn strip(attrs: &mut Vec<syn::Attribute>)`
+///
+/// the arguments of attribute macros and the input of function-like macros eg `bar = 3, baz` can be parsed with
+/// `fn from_args(tokens: impl Into<proc_macro2::TokenStream>)`, `fn from_nested(nested: &[NestedMeta])` or
+/// a `syn::parse::Parse` implementation that's derived with `#[macro_input(parse)]`.
+///
+/// parsed inputs can be emitted as attributes again eg `#[foo(bar = 3, baz)]` with the derived `quote::ToTokens` and `ToMeta` implementations,
+/// `fn to_meta_list(&self, omit_defaults: bool)` or `fn into_attribute(self)`, the types of all fields have to implement `ToMeta`.
+/// leading values are emitted in front of the keys for as long as no earlier leading value is omitted, the other fields are emitted by their keys.
+/// the derived code uses `syn`, `quote` and `proc_macro2`, they have to be dependencies of the crate deriving the input.
+///
+/// parsing doesn't stop at the first error, all missing, mistyped, unknown and duplicate fields are reported together.
+/// `fn try_from_partial(attrs: &[syn::Attribute])` and `fn from_meta_partial(meta: Option<Meta>)` return a `Partial`
+/// with the errors and the value as far as it could be parsed eg for code completion.
+/// errors for missing required fields point at the attribute of the input if there is one, otherwise at the span
+/// passed to `fn try_from_spanned(attrs: &[syn::Attribute], span: proc_macro2::Span)` eg the ident of the item.
+/// arguments are parsed the same way with `fn from_nested_spanned(nested: &[NestedMeta], span: proc_macro2::Span)`
+/// and `fn from_meta_partial_spanned(meta: Option<Meta>, span: proc_macro2::Span)`.
 ///
 /// supported types:
-/// - `u8`, `i32`, `f32`, `char`, `bool`, `String` or `Vec<u8>` for parsing literals eg `#[foo(bar = 3)]`
-/// - `Option<u8>`, `Option<i32>`, `Option<f32>`, `Option<char>`, `Option<bool>`, `Option<String>` or `Option<Vec<u8>>` for optionally parsing literals eg either `#[foo(bar = 3)]` or nothing
-/// - `Option<()>` for parsing flags eg `#[foo(bar)]`
+/// - `i8`-`i128`, `isize`, `u8`-`u128`, `usize`, `f32`, `f64`, `char`, `bool`, `String` or `Vec<u8>` for parsing literals eg `#[foo(bar = 3)]`
+/// - `Option<T>` for any of the above for optionally parsing literals eg either `#[foo(bar = 3)]` or nothing
+/// - `Option<()>` or `Flag` for parsing flags eg `#[foo(bar)]`
+/// - `bool` also accepts flags eg `#[foo(bar)]` is the same as `#[foo(bar = true)]` and `bool`s are false if they're missing
+/// - `Spanned<T>` for remembering the span of a value
+/// - `syn::Path`, `syn::Ident`, `syn::Type`, `syn::Expr` and `syn::WherePredicate` for parsing syntax trees either from strings eg `#[foo(bar = "Vec<u8>")]` or directly eg `#[foo(bar = Vec<u8>)]`
+/// - `Vec<T>` for collecting repeated values eg `#[foo(bar = 1, bar = 2)]`, lists eg `#[foo(bar(1, 2))]` or arrays eg `#[foo(bar = [1, 2])]`
+/// - any other type implementing `FromMeta` or `FromLit`, their default values are checked when parsing
+/// - other types deriving `MacroInput` (or `Box`es of them) for parsing nested lists eg `#[foo(bar(baz = 3))]`
+///
+/// values aren't limited to literals, negative numbers eg `#[foo(bar = -4)]` and any other expression eg `#[foo(bar = some::CONST)]` are accepted as well
+///
+/// generic inputs are supported, type parameters used in fields have to implement `FromMeta`
+///
+/// fields of tuple structs and tuple variants are parsed from leading values in order eg `#[foo("a", 3)]`
 ///
 /// paths get converted to lower_snake unless `rename` is specified
 ///
+/// enums parse exactly one of their variants, the key of a variant is its name in lower_snake unless `rename` is specified:
+/// - unit variants are parsed from flags eg `#[foo(bar)]`
+/// - variants with a single unnamed field are parsed like a field of that type eg `#[foo(bar = 3)]`
+/// - other variants parse their fields from a nested list eg `#[foo(bar(baz = 3))]`
+///
 /// use `#[macro_input]` for customization:
-/// - `rename` to rename either the path or field name eg `#[macro_input(rename = "some_name")]`
+/// - `rename` to rename either the path, the variant or the field name eg `#[macro_input(rename = "some_name")]`
+/// - `alias` to also accept other names for a field or a variant or other paths for the input eg `#[macro_input(alias = "old_name", alias = "other_name")]`, giving a field under more than one of its names is an error
 /// - `default_value` for default values eg `#[macro_input(default_value = "some literal")]`
+/// - `default` to fill a missing field from `Default::default()` or a function eg `#[macro_input(default)]` or `#[macro_input(default = "path::to::fn")]`,
+///   on a struct missing fields are taken from the default value of the whole struct
+/// - `skip` to always fill a field from `Default::default()` instead of an attribute eg `#[macro_input(skip)]`
+/// - `positional` to also accept a named field as a leading value eg `#[foo("/", method = "GET")]`
+/// - `flatten` to parse the keys of a field whose type derives `MacroInput` from the same attributes eg `#[macro_input(flatten)]`, fields that accept the same key are a compile error
+/// - `hidden` to leave a field out of the help text of `FIELD_DEFS.help()` eg `#[macro_input(hidden)]`, the doc comments of fields are their descriptions in the help text
+/// - `example` to add examples to the help text eg `#[macro_input(example = "timeout = 5")]`
+/// - `duplicates` to choose what happens when a field is given more than once eg `#[macro_input(duplicates = "last")]`,
+///   one of `"error"` (the default), `"first"`, `"last"` and `"merge"` for merging nested lists eg `#[foo(bar(a = 1))] #[foo(bar(b = 2))]`
+/// - `validate` to check a value after parsing with a function receiving `&T` and the span of the value eg `#[macro_input(validate = "path::to::fn")]`,
+///   on the input the function receives `&Self`, errors are reported together with the other parse errors
+/// - `range`, `one_of`, `non_empty` and `ident` for the built-in validators in `macro_input::validate` eg `#[macro_input(range = "1..=10")]`,
+///   `#[macro_input(one_of = ["GET", "POST"])]`, `#[macro_input(non_empty)]` for strings or `#[macro_input(ident)]` for identifiers,
+///   validators of `Option`s and `Vec`s check every value that's present
+/// - `conflicts_with` and `requires` to reject a field together with or without other fields eg `#[macro_input(conflicts_with = "async")]`
+///   or `#[macro_input(requires("key", "cert"))]`, `required_if` to require a field if another field has a value eg `#[macro_input(required_if(method = "POST"))]`,
+///   other fields that aren't given have their `default_value`
+/// - `exactly_one_of` and `at_least_one_of` on a struct to require one of some fields eg `#[macro_input(exactly_one_of("sync", "async"))]`
+/// - `parse` on the input to also derive `syn::parse::Parse` for parsing the arguments of macros eg `#[macro_input(parse)]`
+/// - `allow_unknown` on the input to ignore keys that don't belong to any field eg `#[macro_input(allow_unknown)]`, by default they're an error
+/// - `emit = false` on the input to not derive `ToTokens` and `ToMeta` eg for fields with types that don't implement `ToMeta`
+/// - `rename_all` on the input or a variant to change the case of all keys eg `#[macro_input(rename_all = "camelCase")]`, one of `"camelCase"`, `"snake_case"`, `"PascalCase"`, `"SCREAMING_SNAKE_CASE"` and `"lowercase"`
+///
+/// options that take a single value can't be given more than once eg
+/// ```compile_fail
+/// use macro_input_macros::MacroInput;
+///
+/// #[derive(MacroInput)]
+/// pub struct SomeInput {
+///     #[macro_input(default_value = 1, default_value = 2)]
+///     pub bar: u8,
+/// }
+/// ```
+///
+/// raw identifiers are parsed without their prefix eg the key of `r#type` is `type`
 /// # Example
 /// ```
 /// use macro_input_macros::MacroInput;
@@ -66,7 +153,6 @@ use syn::{DeriveInput, Field, LitStr};
 /// # Ok(())
 /// # }
 /// ```
-
 pub fn derive_macro_input(item: TokenStream) -> TokenStream {
     let mut collector = Collector::new();
     let mut ctx = Context::<DeriveInput>::new_parse(&mut collector, item);
@@ -75,29 +161,641 @@ pub fn derive_macro_input(item: TokenStream) -> TokenStream {
     ctx.lint(&input::STRUCT_LINT);
     ctx.lint(&fielddef::Name);
     ctx.lint(&fielddef::FieldType);
+    ctx.lint(&fielddef::Conflicts);
+    ctx.lint(&fielddef::Defaults);
+    ctx.lint(&fielddef::DuplicatePolicy);
+    ctx.lint(&fielddef::Validators);
+    ctx.lint(&fielddef::Constraints);
+    ctx.lint(&fielddef::EmitOrder);
     ctx.lint(&lint::Name);
 
     // expand
     ctx.expand(&convert::TryFromAttributes);
+    ctx.expand(&convert::FromMeta);
+    ctx.expand(&convert::FromMetaBox);
+    ctx.expand(&convert::Partial);
     ctx.expand(&fielddef::ConstFields);
     ctx.expand(&fns::Strip);
+    ctx.expand(&fns::Constructors);
+    ctx.expand(&convert::Parse);
+    ctx.expand(&fns::Emit);
+    ctx.expand(&convert::ToMeta);
+    ctx.expand(&convert::ToMetaBox);
+    ctx.expand(&convert::ToTokens);
 
     collector.finish().into()
 }
 
+#[proc_macro_derive(FromLit, attributes(macro_input))]
+/// automatically derive `FromLit` and `ToLit` for enums without fields
+///
+/// variants are parsed from strings or identifiers eg `mode = "fast"` or `mode = fast`,
+/// the string for a variant is its name in lower_snake unless `rename` is specified
+///
+/// use `#[macro_input]` for customization:
+/// - `rename` to rename a variant eg `#[macro_input(rename = "some_name")]`
+/// - `alias` to also accept other strings for a variant eg `#[macro_input(alias("other_name", "yet_another_name"))]`
+/// - `case_insensitive` on the enum to ignore the case of strings eg `#[macro_input(case_insensitive)]`
+/// - `rename_all` on the enum to change the case of all strings eg `#[macro_input(rename_all = "lowercase")]`
+/// # Example
+/// ```
+/// use macro_input::{Def, DefaultValue};
+/// use macro_input_macros::FromLit;
+/// use syn::{parse_quote, Attribute};
+///
+/// #[derive(FromLit, PartialEq, Debug)]
+/// #[macro_input(case_insensitive)]
+/// pub enum Mode {
+///     #[macro_input(alias("quick"))]
+///     Fast,
+///     Slow,
+/// }
+///
+/// # fn main() -> syn::Result<()> {
+/// const MODE_FIELD: Def = Def::new("foo", "mode", true, DefaultValue::Any(None));
+///
+/// let attr: Attribute = parse_quote!(#[foo(mode = "Quick")]);
+/// assert_eq!(MODE_FIELD.get_value::<Mode>(&[attr])?, Mode::Fast);
+/// let attr: Attribute = parse_quote!(#[foo(mode = slow)]);
+/// assert_eq!(MODE_FIELD.get_value::<Mode>(&[attr])?, Mode::Slow);
+///
+/// let attr: Attribute = parse_quote!(#[foo(mode = "medium")]);
+/// let err = MODE_FIELD.get_value::<Mode>(&[attr]).unwrap_err();
+/// assert_eq!(err.to_string(), r#"expected one of: "fast", "quick", "slow""#);
+/// # Ok(())
+/// # }
+/// ```
+pub fn derive_from_lit(item: TokenStream) -> TokenStream {
+    let mut collector = Collector::new();
+    let mut ctx = Context::<DeriveInput>::new_parse(&mut collector, item);
+
+    // lint
+    ctx.lint(&fromlit::UnitVariants);
+    ctx.lint(&input::FROM_LIT_LINT);
+    ctx.lint(&fromlit::Values);
+
+    // expand
+    ctx.expand(&fromlit::FromLit);
+    ctx.expand(&fromlit::ToLit);
+
+    collector.finish().into()
+}
+
+// the helpers reading `#[macro_input(..)]` fall back to the default for invalid options eg duplicate keys,
+// the errors are reported by `input::STRUCT_LINT` and nothing is expanded after an error
+
+/// the path of the attributes of the input eg `foo` in `#[foo(bar = 3)]`
+fn input_path(input: &DeriveInput) -> String {
+    RENAME_FIELD
+        .get_value::<Option<String>>(&input.attrs)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| input.ident.unraw().to_string().to_snek_case())
+}
+
 fn mod_name(input: &DeriveInput) -> Ident {
-    let path = input.ident.to_string().to_snek_case();
+    let path = input.ident.unraw().to_string().to_snek_case();
     format_ident!("__{}", &*path, span = input.ident.span())
 }
 
-fn field_name(f: &Field) -> (String, Ident) {
-    let (name, span) = RENAME_FIELD.get::<LitStr>(&f.attrs).unwrap().map_or_else(
-        || {
-            let ident = f.ident.as_ref().unwrap();
-            (ident.to_string(), ident.span())
-        },
-        |s| (s.value(), s.span()),
-    );
-    let field_name = format!("{}_field", name).TO_SHOUTY_SNEK_CASE();
-    (name, Ident::new(&*field_name, span))
+/// the case convention of the keys of an input or a variant
+fn rename_all(attrs: &[Attribute]) -> Option<Case> {
+    RENAME_ALL_FIELD
+        .get_value::<Option<Case>>(attrs)
+        .ok()
+        .flatten()
+}
+
+/// the key of the `index`th field, unnamed fields are named by their index eg `0`
+fn field_name(f: &Field, index: usize, case: Option<Case>) -> String {
+    RENAME_FIELD
+        .get_value::<Option<String>>(&f.attrs)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| match &f.ident {
+            Some(ident) => {
+                let name = ident.unraw().to_string();
+                case.map_or_else(|| name.clone(), |case| case.apply(&name))
+            }
+            None => index.to_string(),
+        })
+}
+
+/// the ident of the `Def` of the `index`th field
+fn field_ident(f: &Field, index: usize) -> Ident {
+    if let Ok(Some(s)) = RENAME_FIELD.get::<LitStr>(&f.attrs) {
+        let field_name = format!("{}_field", s.value()).TO_SHOUTY_SNEK_CASE();
+        return Ident::new(&field_name, s.span());
+    }
+
+    match &f.ident {
+        Some(ident) => {
+            let field_name = format!("{}_field", ident.unraw()).TO_SHOUTY_SNEK_CASE();
+            Ident::new(&field_name, ident.span())
+        }
+        None => format_ident!("FIELD_{}", index, span = f.ty.span()),
+    }
+}
+
+/// the index of the leading value for every field
+///
+/// Unnamed fields are positional in order, named fields only if they're marked with `positional`.
+fn field_positions(fields: &Fields) -> Vec<Option<usize>> {
+    let mut next = 0;
+    fields
+        .iter()
+        .map(|f| {
+            let positional = (f.ident.is_none() && !is_flattened(f) && !is_skipped(f))
+                || POSITIONAL_FIELD
+                    .get_value::<Flag>(&f.attrs)
+                    .unwrap_or_default()
+                    .is_set();
+            if positional {
+                next += 1;
+                Some(next - 1)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// the statement checking for keys that don't belong to any field unless they're allowed eg `#[macro_input(allow_unknown)]`
+///
+/// `check` is the call to the check eg `FIELD_DEFS.check_unknown(attrs)`, its error is added to `partial`.
+fn check_unknown(input: &DeriveInput, check: Expr) -> Option<Stmt> {
+    if ALLOW_UNKNOWN_FIELD
+        .get_value::<Flag>(&input.attrs)
+        .unwrap_or_default()
+        .is_set()
+    {
+        None
+    } else {
+        Some(parse_quote!(partial.ok(#check);))
+    }
+}
+
+/// checks whether `syn::parse::Parse` is derived for the input eg `#[macro_input(parse)]`
+fn is_parsed(input: &DeriveInput) -> bool {
+    PARSE_FIELD
+        .get_value::<Flag>(&input.attrs)
+        .unwrap_or_default()
+        .is_set()
+}
+
+/// checks whether the input can be emitted as an attribute unless that's turned off eg `#[macro_input(emit = false)]`
+fn is_emitted(input: &DeriveInput) -> bool {
+    EMIT_FIELD.get_value::<bool>(&input.attrs).unwrap_or(true)
+}
+
+/// checks whether the keys of the field are parsed from the attributes of the input eg `#[macro_input(flatten)]`
+fn is_flattened(f: &Field) -> bool {
+    FLATTEN_FIELD
+        .get_value::<Flag>(&f.attrs)
+        .unwrap_or_default()
+        .is_set()
+}
+
+/// checks whether the field is filled by `Default::default()` instead of an attribute eg `#[macro_input(skip)]`
+fn is_skipped(f: &Field) -> bool {
+    SKIP_FIELD
+        .get_value::<Flag>(&f.attrs)
+        .unwrap_or_default()
+        .is_set()
+}
+
+/// the doc comment of a field or variant with its lines joined eg `request timeout`
+fn doc(attrs: &[Attribute]) -> String {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(syn::MetaNameValue {
+                lit: Lit::Str(doc), ..
+            })) => Some(doc.value()),
+            _ => None,
+        })
+        .map(|line| line.trim().to_owned())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// the name of the type of a single value of a field in the help text eg `i32` for `Option<i32>`
+fn type_name(ty: &Type) -> String {
+    if let Type::Path(tp) = ty {
+        let last = tp.path.segments.last().unwrap();
+        let wrapper = ["Option", "Vec", "Box", "Spanned"]
+            .iter()
+            .any(|wrapper| last.ident == wrapper);
+        if let PathArguments::AngleBracketed(args) = &last.arguments {
+            if let (true, Some(GenericArgument::Type(inner)), 1) =
+                (wrapper, args.args.first(), args.args.len())
+            {
+                if last.ident != "Vec" || !is_byte(inner) {
+                    return type_name(inner);
+                }
+            }
+        }
+    }
+
+    display_type(ty)
+}
+
+/// format a type the way it's usually written eg `Vec<(u8, u16)>` instead of `Vec < (u8 , u16) >`
+fn display_type(ty: &Type) -> String {
+    match ty {
+        Type::Path(tp) if tp.qself.is_none() => display_path(&tp.path),
+        Type::Reference(r) => {
+            let lifetime = r
+                .lifetime
+                .as_ref()
+                .map_or_else(String::new, |lifetime| format!("{} ", lifetime));
+            let mutability = if r.mutability.is_some() { "mut " } else { "" };
+            format!("&{}{}{}", lifetime, mutability, display_type(&r.elem))
+        }
+        Type::Slice(slice) => format!("[{}]", display_type(&slice.elem)),
+        Type::Array(array) => format!(
+            "[{}; {}]",
+            display_type(&array.elem),
+            array.len.to_token_stream()
+        ),
+        Type::Tuple(tuple) if tuple.elems.len() == 1 => {
+            format!("({},)", display_type(&tuple.elems[0]))
+        }
+        Type::Tuple(tuple) => format!("({})", display_types(tuple.elems.iter())),
+        Type::Paren(paren) => format!("({})", display_type(&paren.elem)),
+        Type::Group(group) => display_type(&group.elem),
+        ty => ty.to_token_stream().to_string(),
+    }
+}
+
+/// format a path with its generic arguments eg `std::collections::HashMap<String, i32>`
+fn display_path(path: &Path) -> String {
+    let segments = path
+        .segments
+        .iter()
+        .map(|segment| {
+            let arguments = match &segment.arguments {
+                PathArguments::None => String::new(),
+                PathArguments::AngleBracketed(args) => {
+                    let args = args
+                        .args
+                        .iter()
+                        .map(|arg| match arg {
+                            GenericArgument::Type(ty) => display_type(ty),
+                            GenericArgument::Lifetime(lifetime) => lifetime.to_string(),
+                            arg => arg.to_token_stream().to_string(),
+                        })
+                        .collect::<Vec<_>>();
+                    format!("<{}>", args.join(", "))
+                }
+                PathArguments::Parenthesized(args) => {
+                    let output = match &args.output {
+                        ReturnType::Default => String::new(),
+                        ReturnType::Type(_, ty) => format!(" -> {}", display_type(ty)),
+                    };
+                    format!("({}){}", display_types(args.inputs.iter()), output)
+                }
+            };
+            format!("{}{}", segment.ident, arguments)
+        })
+        .collect::<Vec<_>>();
+    let leading_colon = if path.leading_colon.is_some() {
+        "::"
+    } else {
+        ""
+    };
+    format!("{}{}", leading_colon, segments.join("::"))
+}
+
+/// format a comma separated list of types eg `u8, u16`
+fn display_types<'a>(types: impl Iterator<Item = &'a Type>) -> String {
+    types.map(display_type).collect::<Vec<_>>().join(", ")
+}
+
+/// checks whether the type is `u8`
+fn is_byte(ty: &Type) -> bool {
+    let byte: Type = parse_quote!(u8);
+    *ty == byte
+}
+
+/// the expression computing the default value given by `#[macro_input(default)]` or `#[macro_input(default = "path::to::fn")]`
+fn default_expr(attrs: &[Attribute]) -> Option<Expr> {
+    match DEFAULT_FIELD.get_meta(attrs).ok()?? {
+        Meta::NameValue(_) => {
+            let path = DEFAULT_FIELD.get_value::<Path>(attrs).ok()?;
+            Some(parse_quote!(#path()))
+        }
+        _ => Some(parse_quote!(::core::default::Default::default())),
+    }
+}
+
+/// the checks of the validators of a field eg `#[macro_input(range = "1..=10")]`, each checking `value` at `span`
+fn validators(attrs: &[Attribute]) -> Vec<Expr> {
+    let mut checks = VALIDATE_FIELD
+        .get_all_values::<Path>(attrs)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|path| -> Expr { parse_quote!(#path(value, span)) })
+        .collect::<Vec<_>>();
+    if let Ok(Some(range)) = RANGE_FIELD.get_value::<Option<Expr>>(attrs) {
+        checks.push(parse_quote!(::macro_input::validate::range(value, span, #range)));
+    }
+    let values = ONE_OF_FIELD
+        .get_all_values::<Value>(attrs)
+        .unwrap_or_default();
+    if !values.is_empty() {
+        checks.push(parse_quote!(::macro_input::validate::one_of(value, span, &[#(#values),*])));
+    }
+    if NON_EMPTY_FIELD
+        .get_value::<Flag>(attrs)
+        .unwrap_or_default()
+        .is_set()
+    {
+        checks.push(parse_quote!(::macro_input::validate::non_empty(
+            value, span
+        )));
+    }
+    if IDENT_FIELD
+        .get_value::<Flag>(attrs)
+        .unwrap_or_default()
+        .is_set()
+    {
+        checks.push(parse_quote!(::macro_input::validate::ident(value, span)));
+    }
+    checks
+}
+
+/// the names and values of the fields that make a field required eg `("method", "\"POST\"")` for `#[macro_input(required_if(method = "POST"))]`
+fn required_if(attrs: &[Attribute]) -> syn::Result<Vec<(String, String)>> {
+    let mut conditions = Vec::new();
+    for meta in REQUIRED_IF_FIELD.get_all_metas(attrs)? {
+        let list = <MetaList as FromMeta>::from(Some(meta))?;
+        for nested in list.nested {
+            let condition = match nested {
+                NestedMeta::Meta(Meta::NameValue(mnv)) => (
+                    path_name(&mnv.path)?,
+                    mnv.value.to_token_stream().to_string(),
+                ),
+                NestedMeta::Meta(Meta::Path(path)) => (path_name(&path)?, "true".to_owned()),
+                nested => {
+                    return Err(syn::Error::new_spanned(
+                        nested,
+                        "expected a key and value eg `method = \"POST\"` or a flag",
+                    ))
+                }
+            };
+            conditions.push(condition);
+        }
+    }
+    Ok(conditions)
+}
+
+/// the key named by a path eg `method`
+fn path_name(path: &Path) -> syn::Result<String> {
+    path.get_ident()
+        .map(|ident| ident.unraw().to_string())
+        .ok_or_else(|| syn::Error::new_spanned(path, "expected a key"))
+}
+
+/// the groups of names of a struct-level constraint eg `#[macro_input(exactly_one_of("sync", "async"))]`
+fn groups(def: &Def, attrs: &[Attribute]) -> syn::Result<Vec<Vec<String>>> {
+    def.get_all_metas(attrs)?
+        .into_iter()
+        .map(|meta| {
+            let list = <MetaList as FromMeta>::from(Some(meta))?;
+            <String as FromMeta>::from_list(list)
+        })
+        .collect()
+}
+
+/// all fields of the input including skipped fields
+fn all_fields(input: &DeriveInput) -> Vec<&Field> {
+    match &input.data {
+        Data::Struct(s) => s.fields.iter().collect(),
+        Data::Enum(e) => e.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+        Data::Union(_) => Vec::new(),
+    }
+}
+
+/// all fields that are parsed from a key eg `bar` in `#[foo(bar = 3)]`
+fn keyed_fields(input: &DeriveInput) -> Vec<&Field> {
+    let fields = match &input.data {
+        Data::Struct(s) => s.fields.iter().collect(),
+        Data::Enum(e) => e
+            .variants
+            .iter()
+            .filter(|v| has_nested_fields(v))
+            .flat_map(|v| v.fields.iter())
+            .collect(),
+        Data::Union(_) => Vec::new(),
+    };
+    fields.into_iter().filter(|f| !is_skipped(f)).collect()
+}
+
+/// the generics of the input with a `FromMeta` bound on every type parameter used in a field
+///
+/// Types of fields that are filled by `Default::default()` get a `Default` bound instead.
+fn bounded_generics(input: &DeriveInput) -> Generics {
+    let fields = all_fields(input);
+
+    let mut generics = input.generics.clone();
+    let used_params = used_type_params(input);
+    let defaulted_types = fields
+        .iter()
+        .filter(|f| {
+            let default = is_skipped(f)
+                || matches!(DEFAULT_FIELD.get_meta(&f.attrs), Ok(Some(Meta::Path(_))));
+            default
+                && input
+                    .generics
+                    .type_params()
+                    .any(|param| uses_type_param(&f.ty, &param.ident))
+        })
+        .map(|f| &f.ty)
+        .collect::<Vec<_>>();
+    let has_type_params = input.generics.type_params().next().is_some();
+
+    let where_clause = generics.make_where_clause();
+    for param in used_params {
+        where_clause
+            .predicates
+            .push(parse_quote!(#param: ::macro_input::FromMeta));
+    }
+    for ty in defaulted_types {
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: ::core::default::Default));
+    }
+    if has_type_params
+        && matches!(
+            DEFAULT_FIELD.get_meta(&input.attrs),
+            Ok(Some(Meta::Path(_)))
+        )
+    {
+        let ident = &input.ident;
+        let (_, ty_generics, _) = input.generics.split_for_impl();
+        where_clause
+            .predicates
+            .push(parse_quote!(#ident #ty_generics: ::core::default::Default));
+    }
+    generics
+}
+
+/// the generics of the input with a `ToMeta` bound on every type parameter used in a field
+fn emit_generics(input: &DeriveInput) -> Generics {
+    let mut generics = input.generics.clone();
+    let used_params = used_type_params(input);
+
+    let where_clause = generics.make_where_clause();
+    for param in used_params {
+        where_clause
+            .predicates
+            .push(parse_quote!(#param: ::macro_input::ToMeta));
+    }
+    generics
+}
+
+/// the type parameters used in fields that aren't skipped
+fn used_type_params(input: &DeriveInput) -> Vec<&Ident> {
+    let fields = all_fields(input);
+    input
+        .generics
+        .type_params()
+        .map(|param| &param.ident)
+        .filter(|param| {
+            fields
+                .iter()
+                .any(|f| !is_skipped(f) && uses_type_param(&f.ty, param))
+        })
+        .collect()
+}
+
+/// checks whether the type mentions the type parameter eg `Vec<T>` mentions `T`
+fn uses_type_param(ty: &Type, param: &Ident) -> bool {
+    struct Finder<'a> {
+        param: &'a Ident,
+        found: bool,
+    }
+
+    impl<'ast> Visit<'ast> for Finder<'_> {
+        fn visit_path(&mut self, path: &'ast Path) {
+            if path.leading_colon.is_none()
+                && path.segments.first().map(|s| &s.ident) == Some(self.param)
+            {
+                self.found = true;
+            }
+            visit::visit_path(self, path);
+        }
+    }
+
+    let mut finder = Finder {
+        param,
+        found: false,
+    };
+    finder.visit_type(ty);
+    finder.found
+}
+
+/// the key of a variant and the ident of its `Def`
+fn variant_name(v: &Variant, case: Option<Case>) -> (String, Ident) {
+    if let Ok(Some(s)) = RENAME_FIELD.get::<LitStr>(&v.attrs) {
+        let variant_name = format!("{}_variant", s.value()).TO_SHOUTY_SNEK_CASE();
+        return (s.value(), Ident::new(&variant_name, s.span()));
+    }
+
+    let ident = v.ident.unraw().to_string();
+    let name = case.map_or_else(|| ident.to_snek_case(), |case| case.apply(&ident));
+    let variant_name = format!("{}_variant", ident.to_snek_case()).TO_SHOUTY_SNEK_CASE();
+    (name, Ident::new(&variant_name, v.ident.span()))
+}
+
+fn variant_mod_name(v: &Variant) -> Ident {
+    let path = v.ident.unraw().to_string().to_snek_case();
+    format_ident!("__{}", &*path, span = v.ident.span())
+}
+
+/// all strings a variant of a `FromLit` enum can be parsed from
+fn variant_values(input: &DeriveInput, v: &Variant) -> Vec<String> {
+    let (name, _) = variant_name(v, rename_all(&input.attrs));
+    let aliases = ALIAS_FIELD
+        .get_all_values::<String>(&v.attrs)
+        .unwrap_or_default();
+
+    let case_insensitive = CASE_INSENSITIVE_FIELD
+        .get_value::<Option<()>>(&input.attrs)
+        .map_or(false, |flag| flag.is_some());
+
+    std::iter::once(name)
+        .chain(aliases)
+        .map(|value| {
+            if case_insensitive {
+                value.to_lowercase()
+            } else {
+                value
+            }
+        })
+        .collect()
+}
+
+/// checks whether the variant wraps a single value eg `Get(GetInput)`
+fn is_newtype(v: &Variant) -> bool {
+    matches!(&v.fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1)
+}
+
+/// checks whether the fields of the variant are parsed from a nested list eg `get(path = "/")`
+fn has_nested_fields(v: &Variant) -> bool {
+    !matches!(v.fields, Fields::Unit) && !is_newtype(v)
+}
+
+/// checks whether the type is `Option<T>`, `std::option::Option<T>` or `core::option::Option<T>`
+fn is_optional(ty: &Type) -> bool {
+    if let Type::Path(tp) = ty {
+        let segments = tp
+            .path
+            .segments
+            .iter()
+            .map(|s| &s.ident)
+            .collect::<Vec<_>>();
+        match segments.as_slice() {
+            [option] => tp.path.leading_colon.is_none() && *option == "Option",
+            [krate, module, option] => {
+                (*krate == "std" || *krate == "core") && *module == "option" && *option == "Option"
+            }
+            _ => false,
+        }
+    } else {
+        false
+    }
+}
+
+/// checks whether the type is `Vec<T>` or `std::vec::Vec<T>` but not a byte string (`Vec<u8>`)
+fn is_repeated(ty: &Type) -> bool {
+    if let Type::Path(tp) = ty {
+        let segments = tp
+            .path
+            .segments
+            .iter()
+            .map(|s| &s.ident)
+            .collect::<Vec<_>>();
+        let is_vec = match segments.as_slice() {
+            [vec] => tp.path.leading_colon.is_none() && *vec == "Vec",
+            [krate, module, vec] => {
+                (*krate == "std" || *krate == "alloc") && *module == "vec" && *vec == "Vec"
+            }
+            _ => false,
+        };
+
+        let is_bytes = match &tp.path.segments.last().unwrap().arguments {
+            PathArguments::AngleBracketed(args) => {
+                matches!(args.args.first(), Some(GenericArgument::Type(ty)) if is_byte(ty))
+            }
+            _ => false,
+        };
+
+        is_vec && !is_bytes
+    } else {
+        false
+    }
 }
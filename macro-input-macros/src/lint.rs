@@ -1,21 +1,48 @@
-use crate::RENAME_FIELD;
+use crate::{case::Case, ALIAS_FIELD, RENAME_ALL_FIELD, RENAME_FIELD};
 use macro_compose::{Collector, Lint};
-use proc_macro2::Ident;
-use syn::{parse_str, DeriveInput, Error};
+use macro_input_core::{validate, Spanned};
+use proc_macro2::Span;
+use syn::{Attribute, Data, DeriveInput};
 
 pub struct Name;
 
 impl Lint<DeriveInput> for Name {
     fn lint(&self, input: &DeriveInput, c: &mut Collector) {
-        if let Some(name) = RENAME_FIELD
-            .get_value::<Option<String>>(&input.attrs)
-            .unwrap()
-        {
-            if let Err(e) = parse_str::<Ident>(&name) {
-                let meta = RENAME_FIELD.get_lit(&input.attrs).unwrap();
-                let e = Error::new_spanned(meta, e);
-                c.error(e);
+        // the path of the attributes can't be a keyword eg `#[type(..)]` isn't an attribute
+        lint_rename(&input.attrs, validate::ident, c);
+
+        // the names of variants are keys eg `#[foo(type = 1)]`
+        if let Data::Enum(e) = &input.data {
+            for variant in e.variants.iter() {
+                lint_rename(&variant.attrs, validate::key, c);
             }
         }
     }
 }
+
+fn lint_rename(
+    attrs: &[Attribute],
+    check: fn(&String, Span) -> syn::Result<()>,
+    c: &mut Collector,
+) {
+    let names = RENAME_FIELD
+        .get_value::<Option<Spanned<String>>>(attrs)
+        .map(|rename| rename.into_iter().collect::<Vec<_>>())
+        .and_then(|mut names| {
+            names.extend(ALIAS_FIELD.get_all_values::<Spanned<String>>(attrs)?);
+            Ok(names)
+        });
+    match names {
+        Ok(names) => {
+            for name in names {
+                if let Err(e) = check(&name, name.span()) {
+                    c.error(e);
+                }
+            }
+        }
+        Err(e) => c.error(e),
+    }
+    if let Err(e) = RENAME_ALL_FIELD.get_value::<Option<Case>>(attrs) {
+        c.error(e);
+    }
+}
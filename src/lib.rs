@@ -1,3 +1,6 @@
-pub use macro_input_core::{Def, DefaultValue, Defs, FromLit, FromMeta, Type, Types};
+pub use macro_input_core::{
+    parse_meta, validate, Def, DefaultValue, Defs, Duplicates, Flag, FromLit, FromMeta, Meta,
+    MetaList, MetaNameValue, NestedMeta, Partial, Spanned, ToLit, ToMeta, Type, Types, Value,
+};
 #[cfg(feature = "macros")]
 pub use macro_input_macros::*;
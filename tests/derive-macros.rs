@@ -1,6 +1,12 @@
-use macro_input::MacroInput;
-use std::{convert::TryFrom, fmt::Debug};
-use syn::{parse_quote, Attribute};
+use macro_input::{Flag, FromLit, FromMeta, MacroInput, Meta, MetaList, Spanned};
+use quote::ToTokens;
+use std::{borrow::Cow, convert::TryFrom, fmt::Debug};
+use syn::{parse::Parser, parse_quote, Attribute, Error, Lit};
+
+/// parse attributes from a string so that their spans have locations
+fn parse_attrs(s: &str) -> Vec<Attribute> {
+    Attribute::parse_outer.parse_str(s).unwrap()
+}
 
 #[derive(MacroInput, PartialEq, Debug)]
 pub struct Input {
@@ -62,3 +68,1538 @@ fn test_other_input() {
         OtherInput { renamed: 3 },
     );
 }
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct NumericInput {
+    pub port: u16,
+    #[macro_input(default_value = 4096)]
+    pub size: u64,
+    pub index: Option<usize>,
+    #[macro_input(default_value = 0.5)]
+    pub ratio: f64,
+    pub byte: u8,
+}
+
+#[test]
+fn test_numeric_input() {
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[numeric_input(port = 8080, byte = 7)])];
+    let res = NumericInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        NumericInput {
+            port: 8080,
+            size: 4096,
+            index: None,
+            ratio: 0.5,
+            byte: 7,
+        }
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[numeric_input(port = 70000, byte = 7)])];
+    assert!(NumericInput::try_from(attrs.as_slice()).is_err());
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Port(u16);
+
+impl FromLit for Port {
+    fn from(lit: Option<Lit>) -> syn::Result<Self> {
+        <u16 as FromLit>::from(lit).map(Port)
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Name(String);
+
+impl FromMeta for Name {
+    fn from(meta: Option<Meta>) -> syn::Result<Self> {
+        match meta {
+            Some(Meta::Path(path)) => Ok(Name(path.get_ident().unwrap().to_string())),
+            meta => Err(Error::new_spanned(meta, "expected a name")),
+        }
+    }
+}
+
+/// `Port` and `Name` can only be parsed
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(emit = false)]
+pub struct CustomInput {
+    pub port: Port,
+    #[macro_input(default_value = 80)]
+    pub default_port: Port,
+    pub name: Option<Name>,
+    pub lit: Lit,
+    pub std_optional: std::option::Option<i32>,
+    pub core_optional: ::core::option::Option<Port>,
+}
+
+#[test]
+fn test_custom_input() {
+    let attrs: Vec<Attribute> =
+        vec![parse_quote!(#[custom_input(port = 8080, name(foo), lit = 'c')])];
+    let res = CustomInput::try_from(attrs.as_slice()).unwrap_err();
+    assert_eq!(res.to_string(), "expected a name");
+
+    let attrs: Vec<Attribute> =
+        vec![parse_quote!(#[custom_input(port = 8080, lit = 'c', core_optional = 1)])];
+    let res = CustomInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        CustomInput {
+            port: Port(8080),
+            default_port: Port(80),
+            name: None,
+            lit: parse_quote!('c'),
+            std_optional: None,
+            core_optional: Some(Port(1)),
+        }
+    );
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct Auth {
+    pub user: String,
+    #[macro_input(default_value = "default")]
+    pub realm: String,
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct Route {
+    pub auth: Auth,
+    pub fallback: Option<Auth>,
+    pub timeout: i32,
+}
+
+#[test]
+fn test_nested_input() {
+    let attrs: Vec<Attribute> =
+        vec![parse_quote!(#[route(auth(user = "x", realm = "y"), timeout = 5)])];
+    let res = Route::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        Route {
+            auth: Auth {
+                user: "x".to_string(),
+                realm: "y".to_string(),
+            },
+            fallback: None,
+            timeout: 5,
+        }
+    );
+
+    let attrs: Vec<Attribute> =
+        vec![parse_quote!(#[route(auth(user = "x"), fallback(user = "z"), timeout = 5)])];
+    let res = Route::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res.fallback,
+        Some(Auth {
+            user: "z".to_string(),
+            realm: "default".to_string(),
+        })
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[route(auth(realm = "y"), timeout = 5)])];
+    let err = Route::try_from(attrs.as_slice()).unwrap_err();
+    assert_eq!(err.to_string(), "field required by auth not found: user");
+
+    let attrs: Vec<Attribute> =
+        vec![parse_quote!(#[route(auth(user = "x", uesr = "y"), timeout = 5)])];
+    let err = Route::try_from(attrs.as_slice()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unrecognized attribute: auth::uesr, expected one of: user, realm"
+    );
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct Tree {
+    pub value: i32,
+    pub child: Option<Box<Tree>>,
+}
+
+#[test]
+fn test_recursive_input() {
+    let attrs: Vec<Attribute> =
+        vec![parse_quote!(#[tree(value = 1, child(value = 2, child(value = 3)))])];
+    let res = Tree::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        Tree {
+            value: 1,
+            child: Some(Box::new(Tree {
+                value: 2,
+                child: Some(Box::new(Tree {
+                    value: 3,
+                    child: None,
+                })),
+            })),
+        }
+    );
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(rename = "endpoint")]
+pub enum Endpoint {
+    Get {
+        path: String,
+    },
+    Post {
+        path: String,
+        #[macro_input(default_value = "json")]
+        body: String,
+    },
+    Head,
+    #[macro_input(rename = "auth")]
+    Authenticated(Auth),
+    Tuple(
+        #[macro_input(rename = "a")] i32,
+        #[macro_input(rename = "b")] i32,
+    ),
+}
+
+#[test]
+fn test_enum_input() {
+    fn test_input(attr: Attribute, value: Endpoint) {
+        let attrs = vec![attr];
+
+        let res = Endpoint::try_from(attrs.as_slice()).unwrap();
+        assert_eq!(value, res);
+    }
+
+    test_input(
+        parse_quote!(#[endpoint(get(path = "/"))]),
+        Endpoint::Get {
+            path: "/".to_string(),
+        },
+    );
+    test_input(
+        parse_quote!(#[endpoint(post(path = "/users"))]),
+        Endpoint::Post {
+            path: "/users".to_string(),
+            body: "json".to_string(),
+        },
+    );
+    test_input(parse_quote!(#[endpoint(head)]), Endpoint::Head);
+    test_input(
+        parse_quote!(#[endpoint(auth(user = "x"))]),
+        Endpoint::Authenticated(Auth {
+            user: "x".to_string(),
+            realm: "default".to_string(),
+        }),
+    );
+    test_input(
+        parse_quote!(#[endpoint(tuple(a = 1, b = 2))]),
+        Endpoint::Tuple(1, 2),
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[endpoint()])];
+    let err = Endpoint::try_from(attrs.as_slice()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "expected one of: get, post, head, auth, tuple"
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[endpoint(head, get(path = "/"))])];
+    let err = Endpoint::try_from(attrs.as_slice()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "only one of get, post, head, auth, tuple may be specified"
+    );
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct Server {
+    pub endpoint: Endpoint,
+}
+
+#[test]
+fn test_nested_enum_input() {
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[server(endpoint(get(path = "/")))])];
+    let res = Server::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        Server {
+            endpoint: Endpoint::Get {
+                path: "/".to_string()
+            }
+        }
+    );
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Trait(String);
+
+impl FromMeta for Trait {
+    fn from(meta: Option<Meta>) -> syn::Result<Self> {
+        match meta {
+            Some(Meta::Path(path)) => Ok(Trait(path.get_ident().unwrap().to_string())),
+            meta => Err(Error::new_spanned(meta, "expected a trait")),
+        }
+    }
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(emit = false)]
+pub struct ListInput {
+    pub derive: Vec<String>,
+    pub traits: Vec<Trait>,
+    pub auth: Vec<Auth>,
+    pub bytes: Option<Vec<u8>>,
+}
+
+#[test]
+fn test_list_input() {
+    let attrs: Vec<Attribute> = vec![
+        parse_quote!(#[list_input(derive = "A", derive = "B", traits(Clone, Debug))]),
+        parse_quote!(#[list_input(derive("C", "D"), auth(user = "x"), auth(user = "y"))]),
+    ];
+    let res = ListInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        ListInput {
+            derive: vec![
+                "A".to_string(),
+                "B".to_string(),
+                "C".to_string(),
+                "D".to_string()
+            ],
+            traits: vec![Trait("Clone".to_string()), Trait("Debug".to_string())],
+            auth: vec![
+                Auth {
+                    user: "x".to_string(),
+                    realm: "default".to_string(),
+                },
+                Auth {
+                    user: "y".to_string(),
+                    realm: "default".to_string(),
+                }
+            ],
+            bytes: None,
+        }
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[list_input(bytes = b"ab")])];
+    let res = ListInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(res.bytes, Some(b"ab".to_vec()));
+    assert!(res.derive.is_empty());
+}
+
+#[derive(FromLit, PartialEq, Debug)]
+pub enum Mode {
+    Fast,
+    #[macro_input(rename = "slow", alias = "careful")]
+    SlowAndSteady,
+}
+
+#[derive(FromLit, PartialEq, Debug)]
+#[macro_input(case_insensitive)]
+pub enum Speed {
+    Fast,
+    #[macro_input(alias = "Crawl")]
+    Slow,
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct ModeInput {
+    pub mode: Mode,
+    #[macro_input(default_value = "fast")]
+    pub fallback: Mode,
+    pub modes: Vec<Mode>,
+    pub speed: Option<Speed>,
+}
+
+#[test]
+fn test_from_lit_enum() {
+    let attrs: Vec<Attribute> =
+        vec![parse_quote!(#[mode_input(mode = "careful", modes("fast", "slow"))])];
+    let res = ModeInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        ModeInput {
+            mode: Mode::SlowAndSteady,
+            fallback: Mode::Fast,
+            modes: vec![Mode::Fast, Mode::SlowAndSteady],
+            speed: None,
+        }
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[mode_input(mode = "Fast")])];
+    let err = ModeInput::try_from(attrs.as_slice()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"expected one of: "fast", "slow", "careful""#
+    );
+
+    // values can be identifiers too
+    let attrs: Vec<Attribute> =
+        vec![parse_quote!(#[mode_input(mode = careful, modes(fast, r#slow), speed = crawl)])];
+    let res = ModeInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(res.mode, Mode::SlowAndSteady);
+    assert_eq!(res.modes, vec![Mode::Fast, Mode::SlowAndSteady]);
+    assert_eq!(res.speed, Some(Speed::Slow));
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[mode_input(mode = fast::path)])];
+    let err = ModeInput::try_from(attrs.as_slice()).unwrap_err();
+    assert_eq!(err.to_string(), "expected string or identifier");
+
+    // case insensitive values
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[mode_input(mode = "fast", speed = "FAST")])];
+    let res = ModeInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(res.speed, Some(Speed::Fast));
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[mode_input(mode = "fast", speed = "CRAWL")])];
+    let res = ModeInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(res.speed, Some(Speed::Slow));
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct SpannedInput {
+    pub debug: Flag,
+    pub verbose: Flag,
+    pub timeout: Spanned<u32>,
+    pub name: Option<Spanned<String>>,
+    pub tags: Vec<Spanned<String>>,
+}
+
+#[test]
+fn test_spanned_input() {
+    let attrs: Vec<Attribute> =
+        vec![parse_quote!(#[spanned_input(debug, timeout = 5, tags("a", "b"))])];
+    let res = SpannedInput::try_from(attrs.as_slice()).unwrap();
+    assert!(res.debug.is_set());
+    assert!(res.debug.span().is_some());
+    assert!(!res.verbose.is_set());
+    assert_eq!(*res.timeout, 5);
+    assert_eq!(res.name, None);
+    assert_eq!(
+        res.tags
+            .into_iter()
+            .map(Spanned::into_inner)
+            .collect::<Vec<_>>(),
+        vec!["a", "b"]
+    );
+
+    // every value of a list has its own span
+    let attrs = parse_attrs(r#"#[spanned_input(timeout = 5, tags("a", "b"), tags = "c")]"#);
+    let res = SpannedInput::try_from(attrs.as_slice()).unwrap();
+    let columns = res
+        .tags
+        .iter()
+        .map(|tag| tag.span().start().column)
+        .collect::<Vec<_>>();
+    assert_eq!(columns, vec![34, 39, 45]);
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[spanned_input(debug = 1, timeout = 5)])];
+    let err = SpannedInput::try_from(attrs.as_slice()).unwrap_err();
+    assert_eq!(err.to_string(), "unexpected value");
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct BoolInput {
+    pub enabled: bool,
+    pub disabled: bool,
+    #[macro_input(default_value = true)]
+    pub on_by_default: bool,
+    pub missing: bool,
+}
+
+#[test]
+fn test_bool_input() {
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[bool_input(enabled, disabled = false)])];
+    let res = BoolInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        BoolInput {
+            enabled: true,
+            disabled: false,
+            on_by_default: true,
+            missing: false,
+        }
+    );
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct SyntaxInput {
+    pub with: Option<syn::Path>,
+    pub ty: syn::Type,
+    #[macro_input(default_value = "Vec::new()")]
+    pub default: syn::Expr,
+}
+
+#[test]
+fn test_syntax_input() {
+    let attrs: Vec<Attribute> =
+        vec![parse_quote!(#[syntax_input(with = "my::module", ty = "Vec<u8>")])];
+    let res = SyntaxInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        SyntaxInput {
+            with: Some(parse_quote!(my::module)),
+            ty: parse_quote!(Vec<u8>),
+            default: parse_quote!(Vec::new()),
+        }
+    );
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct ValueInput {
+    #[macro_input(default_value = -1)]
+    pub offset: i32,
+    pub kind: syn::Path,
+    pub value: syn::Expr,
+    pub sizes: Vec<i64>,
+    #[macro_input(default_value = some::DEFAULT)]
+    pub fallback: syn::Expr,
+}
+
+#[test]
+fn test_value_input() {
+    let attrs: Vec<Attribute> = vec![parse_quote!(
+        #[value_input(kind = Json, value = (1, 2), sizes = [-1, 2, 3])]
+    )];
+    let res = ValueInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        ValueInput {
+            offset: -1,
+            kind: parse_quote!(Json),
+            value: parse_quote!((1, 2)),
+            sizes: vec![-1, 2, 3],
+            fallback: parse_quote!(some::DEFAULT),
+        }
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(
+        #[value_input(offset = -4, kind = Json, value = some::CONST)]
+    )];
+    let res = ValueInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(res.offset, -4);
+    assert_eq!(res.value, parse_quote!(some::CONST));
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Label<'a>(Cow<'a, str>);
+
+impl FromLit for Label<'_> {
+    fn from(lit: Option<Lit>) -> syn::Result<Self> {
+        <String as FromLit>::from(lit).map(|label| Label(Cow::Owned(label)))
+    }
+}
+
+impl macro_input::ToLit for Label<'_> {
+    fn to_value(&self) -> macro_input::Value {
+        self.0.to_string().to_value()
+    }
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct GenericInput<'a, T>
+where
+    T: Debug,
+{
+    pub value: T,
+    pub values: Vec<T>,
+    pub optional: Option<Box<GenericInput<'a, T>>>,
+    pub label: Option<Label<'a>>,
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub enum GenericEnum<T> {
+    Single(T),
+    Nested { value: T },
+}
+
+#[test]
+fn test_generic_input() {
+    let attrs: Vec<Attribute> = vec![parse_quote!(
+        #[generic_input(value = 1, values(2, 3), optional(value = 4), label = "x")]
+    )];
+    let res = GenericInput::<i32>::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        GenericInput {
+            value: 1,
+            values: vec![2, 3],
+            optional: Some(Box::new(GenericInput {
+                value: 4,
+                values: vec![],
+                optional: None,
+                label: None,
+            })),
+            label: Some(Label(Cow::Borrowed("x"))),
+        }
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[generic_enum(nested(value = "a"))])];
+    let res = GenericEnum::<String>::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        GenericEnum::Nested {
+            value: "a".to_string()
+        }
+    );
+
+    let mut attrs = attrs;
+    GenericEnum::<String>::strip(&mut attrs);
+    assert!(attrs.is_empty());
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct Tag(String, Option<i32>);
+
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(rename = "get", parse)]
+pub struct GetRoute {
+    #[macro_input(positional)]
+    pub path: String,
+    pub method: Option<String>,
+    pub auth: Option<Auth>,
+}
+
+#[test]
+fn test_positional_input() {
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[tag("a", 3)])];
+    let res = Tag::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(res, Tag("a".to_string(), Some(3)));
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[tag("a")])];
+    let res = Tag::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(res, Tag("a".to_string(), None));
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[get("/users", method = "GET")])];
+    let res = GetRoute::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        GetRoute {
+            path: "/users".to_string(),
+            method: Some("GET".to_string()),
+            auth: None,
+        }
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[get(path = "/users")])];
+    let res = GetRoute::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(res.path, "/users");
+
+    let mut attrs: Vec<Attribute> = vec![parse_quote!(#[get("/users", method = "GET")])];
+    GetRoute::strip(&mut attrs);
+    assert!(attrs.is_empty());
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct Router {
+    pub get: Tag,
+}
+
+#[test]
+fn test_nested_positional_input() {
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[router(get("a", 3))])];
+    let res = Router::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(res.get, Tag("a".to_string(), Some(3)));
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[router(get("a", 3, 4))])];
+    let err = Router::try_from(attrs.as_slice()).unwrap_err();
+    assert_eq!(err.to_string(), "unexpected positional argument");
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(rename_all = "camelCase")]
+pub struct CamelInput {
+    pub max_size: u32,
+    #[macro_input(rename = "min")]
+    pub min_size: u32,
+    pub r#type: String,
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ScreamingEnum {
+    FastMode,
+    #[macro_input(rename_all = "PascalCase")]
+    SlowMode {
+        max_delay: u32,
+    },
+}
+
+#[derive(FromLit, PartialEq, Debug)]
+#[macro_input(rename_all = "lowercase")]
+pub enum LowerMode {
+    FastMode,
+    SlowMode,
+}
+
+#[test]
+fn test_rename_all() {
+    let attrs: Vec<Attribute> =
+        vec![parse_quote!(#[camel_input(maxSize = 3, min = 1, type = "x")])];
+    let res = CamelInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        CamelInput {
+            max_size: 3,
+            min_size: 1,
+            r#type: "x".to_string(),
+        }
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[screaming_enum(FAST_MODE)])];
+    let res = ScreamingEnum::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(res, ScreamingEnum::FastMode);
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[screaming_enum(SLOW_MODE(MaxDelay = 3))])];
+    let res = ScreamingEnum::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(res, ScreamingEnum::SlowMode { max_delay: 3 });
+
+    let lit: Lit = parse_quote!("slowmode");
+    assert_eq!(
+        <LowerMode as FromLit>::from(Some(lit)).unwrap(),
+        LowerMode::SlowMode
+    );
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(rename = "my_macro", alias = "mm")]
+pub struct AliasInput {
+    #[macro_input(alias = "len", alias = "length")]
+    pub size: Option<u32>,
+    #[macro_input(alias("old_name"))]
+    pub name: Option<String>,
+}
+
+#[test]
+fn test_alias_input() {
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[my_macro(len = 3, old_name = "a")])];
+    let res = AliasInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        AliasInput {
+            size: Some(3),
+            name: Some("a".to_string()),
+        }
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[mm(length = 4)])];
+    let res = AliasInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(res.size, Some(4));
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[my_macro(size = 3, len = 4)])];
+    let err = AliasInput::try_from(attrs.as_slice()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "duplicate size attribute: size and len are the same field"
+    );
+
+    let mut attrs: Vec<Attribute> = vec![
+        parse_quote!(#[my_macro(len = 3)]),
+        parse_quote!(#[mm(old_name = "a", other = 1)]),
+    ];
+    AliasInput::strip(&mut attrs);
+    let remaining: Attribute = parse_quote!(#[mm(other = 1)]);
+    assert_eq!(attrs, vec![remaining]);
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct CommonOptions {
+    pub debug: bool,
+    #[macro_input(rename = "crate")]
+    pub krate: Option<String>,
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct FlattenInput {
+    pub name: String,
+    #[macro_input(flatten)]
+    pub common: CommonOptions,
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct NestedFlattenInput {
+    pub inner: FlattenInput,
+}
+
+#[test]
+fn test_flatten_input() {
+    let attrs: Vec<Attribute> = vec![
+        parse_quote!(#[flatten_input(name = "a", debug)]),
+        parse_quote!(#[flatten_input(crate = "my_crate")]),
+    ];
+    let res = FlattenInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        FlattenInput {
+            name: "a".to_string(),
+            common: CommonOptions {
+                debug: true,
+                krate: Some("my_crate".to_string()),
+            },
+        }
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[flatten_input(name = "a")])];
+    let res = FlattenInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res.common,
+        CommonOptions {
+            debug: false,
+            krate: None,
+        }
+    );
+
+    let attrs: Vec<Attribute> =
+        vec![parse_quote!(#[nested_flatten_input(inner(name = "a", debug))])];
+    let res = NestedFlattenInput::try_from(attrs.as_slice()).unwrap();
+    assert!(res.inner.common.debug);
+
+    let attrs: Vec<Attribute> =
+        vec![parse_quote!(#[nested_flatten_input(inner(name = "a", other))])];
+    assert!(NestedFlattenInput::try_from(attrs.as_slice()).is_err());
+
+    let mut attrs: Vec<Attribute> = vec![parse_quote!(#[flatten_input(name = "a", debug, other)])];
+    FlattenInput::strip(&mut attrs);
+    let remaining: Attribute = parse_quote!(#[flatten_input(other)]);
+    assert_eq!(attrs, vec![remaining]);
+}
+
+fn default_retries() -> u32 {
+    3
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct DefaultInput {
+    pub name: String,
+    #[macro_input(skip)]
+    pub cache: Vec<String>,
+    #[macro_input(default)]
+    pub timeout: u64,
+    #[macro_input(default = "default_retries")]
+    pub retries: u32,
+    #[macro_input(default = default_retries)]
+    pub backoff: u32,
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(default)]
+pub struct StructDefaultInput {
+    pub name: String,
+    pub size: u32,
+    #[macro_input(default = "default_retries")]
+    pub retries: u32,
+}
+
+impl Default for StructDefaultInput {
+    fn default() -> Self {
+        StructDefaultInput {
+            name: "default".to_string(),
+            size: 7,
+            retries: 0,
+        }
+    }
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct SkipTuple(#[macro_input(skip)] Option<u32>, String);
+
+#[test]
+fn test_default_input() {
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[default_input(name = "a")])];
+    let res = DefaultInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        DefaultInput {
+            name: "a".to_string(),
+            cache: Vec::new(),
+            timeout: 0,
+            retries: 3,
+            backoff: 3,
+        }
+    );
+
+    let attrs: Vec<Attribute> =
+        vec![parse_quote!(#[default_input(name = "a", timeout = 5, retries = 1)])];
+    let res = DefaultInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!((res.timeout, res.retries), (5, 1));
+
+    // skipped fields don't have a key
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[default_input(name = "a", cache = "b")])];
+    assert!(DefaultInput::try_from(attrs.as_slice()).is_err());
+    let list: MetaList = parse_quote!(default_input(name = "a", cache = "b"));
+    assert!(<DefaultInput as FromMeta>::from(Some(Meta::List(list))).is_err());
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[struct_default_input(size = 1)])];
+    let res = StructDefaultInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        StructDefaultInput {
+            name: "default".to_string(),
+            size: 1,
+            retries: 3,
+        }
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[skip_tuple("a")])];
+    let res = SkipTuple::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(res, SkipTuple(None, "a".to_string()));
+}
+
+#[test]
+fn test_args_input() {
+    let expected = Input {
+        flag: Some(()),
+        optional: None,
+        with_default: 3,
+        required: 5,
+    };
+
+    let tokens: proc_macro2::TokenStream = "flag, required = 5".parse().unwrap();
+    let res = Input::from_args(tokens).unwrap();
+    assert_eq!(res, expected);
+
+    let list: MetaList = parse_quote!(input(flag, required = 5));
+    let nested = list.nested.into_iter().collect::<Vec<_>>();
+    let res = Input::from_nested(&nested).unwrap();
+    assert_eq!(res, expected);
+
+    let err = Input::from_args(proc_macro2::TokenStream::new()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "field required by input not found: required"
+    );
+    let from_str = |s: &str| Input::from_args(s.parse::<proc_macro2::TokenStream>().unwrap());
+    assert!(from_str("required = 5, other = 1").is_err());
+    assert!(from_str("required = ").is_err());
+
+    let res: GetRoute = syn::parse_str(r#""/users", method = "GET""#).unwrap();
+    assert_eq!(res.path, "/users");
+
+    let res: OwnParse = syn::parse_str("anything").unwrap();
+    assert_eq!(res, OwnParse { required: 0 });
+    let tokens: proc_macro2::TokenStream = "required = 5".parse().unwrap();
+    assert_eq!(
+        OwnParse::from_args(tokens).unwrap(),
+        OwnParse { required: 5 }
+    );
+}
+
+/// `syn::parse::Parse` is only derived with `#[macro_input(parse)]`
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct OwnParse {
+    pub required: i32,
+}
+
+impl syn::parse::Parse for OwnParse {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        input.parse::<syn::Ident>()?;
+        Ok(OwnParse { required: 0 })
+    }
+}
+
+#[derive(FromLit, PartialEq, Debug)]
+pub enum Level {
+    Low,
+    #[macro_input(rename = "HIGH")]
+    High,
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct EmitCommon {
+    #[macro_input(rename = "crate")]
+    pub krate: Option<syn::Path>,
+    pub verbose: bool,
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct EmitInner {
+    pub depth: u8,
+    pub tags: Vec<String>,
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub enum EmitMode {
+    Fast,
+    Limit(u32),
+    Custom { depth: u8 },
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(rename = "emitted")]
+pub struct EmitInput {
+    pub name: String,
+    pub flag: Flag,
+    pub unit: Option<()>,
+    pub enabled: bool,
+    #[macro_input(default_value = 3)]
+    pub retries: u32,
+    pub offset: i64,
+    pub ratio: f64,
+    pub letter: char,
+    pub bytes: Vec<u8>,
+    pub ty: Option<syn::Type>,
+    pub level: Option<Level>,
+    pub inner: Option<Box<EmitInner>>,
+    pub mode: EmitMode,
+    pub values: Vec<Spanned<i32>>,
+    #[macro_input(flatten)]
+    pub common: EmitCommon,
+    #[macro_input(skip)]
+    pub cache: Option<String>,
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct EmitTag(String, Option<i32>);
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct EmitLeading(bool, Option<i32>, #[macro_input(rename = "b")] Option<i32>);
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct EmitRoute {
+    pub timeout: Option<i32>,
+    #[macro_input(positional)]
+    pub path: Option<String>,
+    #[macro_input(positional)]
+    pub method: Option<String>,
+}
+
+#[test]
+fn test_emit_input() {
+    /// parse, emit and parse again
+    fn round_trip<T>(attr: Attribute, emit: impl Fn(&T) -> Attribute)
+    where
+        T: for<'a> TryFrom<&'a [Attribute], Error = Error> + PartialEq + Debug,
+    {
+        let value = T::try_from(&[attr]).unwrap();
+        let res = T::try_from(&[emit(&value)]).unwrap();
+        assert_eq!(res, value);
+    }
+
+    let attr: Attribute = parse_quote!(#[emitted(
+        name = "a \"quoted\" name",
+        flag,
+        unit,
+        enabled,
+        retries = 3,
+        offset = -4,
+        ratio = 0.25,
+        letter = 'x',
+        bytes = b"\x00\xff",
+        ty = "Vec<(u8, u16)>",
+        level = "HIGH",
+        inner(depth = 2, tags("a", "b")),
+        mode(custom(depth = 1)),
+        values = 1,
+        values(2, -3),
+        crate = "::krate",
+        verbose = false,
+    )]);
+    round_trip::<EmitInput>(attr.clone(), |value| {
+        value.to_meta_list(false).into_attribute()
+    });
+    round_trip::<EmitInput>(attr.clone(), |value| {
+        value.to_meta_list(true).into_attribute()
+    });
+    round_trip::<EmitInput>(attr, |value| parse_quote!(#value));
+
+    let attr: Attribute = parse_quote!(#[emitted(name = "b", mode(limit = 5), offset = 0, ratio = 1, letter = 'y', bytes = b"")]);
+    let value = EmitInput::try_from(std::slice::from_ref(&attr)).unwrap();
+    let emitted = value.to_meta_list(true);
+    assert_eq!(
+        emitted.to_token_stream().to_string(),
+        r#"emitted (name = "b" , offset = 0 , ratio = 1.0 , letter = 'y' , bytes = b"" , mode (limit = 5))"#
+    );
+    round_trip::<EmitInput>(attr, |value| parse_quote!(#value));
+
+    let attr: Attribute = parse_quote!(#[emitted(name = "c", mode(fast), offset = 1, ratio = 1.5, letter = 'z', bytes = b"z", retries = 4)]);
+    round_trip::<EmitInput>(attr, |value| value.to_meta_list(true).into_attribute());
+
+    let attr: Attribute = parse_quote!(#[emit_tag("a", 3)]);
+    let value = EmitTag::try_from(std::slice::from_ref(&attr)).unwrap();
+    assert_eq!(
+        value.to_token_stream().to_string(),
+        attr.to_token_stream().to_string()
+    );
+    assert_eq!(value.into_attribute(), attr);
+
+    // leading values are emitted by their keys once an earlier one is omitted
+    for (attr, emitted) in [
+        (
+            parse_quote!(#[emit_leading(true, 1, 2)]),
+            "emit_leading (true , 1 , 2)",
+        ),
+        (
+            parse_quote!(#[emit_leading(false, b = 2)]),
+            "emit_leading (false , b = 2)",
+        ),
+        (
+            parse_quote!(#[emit_route("/", "GET", timeout = 1)]),
+            r#"emit_route ("/" , "GET" , timeout = 1)"#,
+        ),
+        (
+            parse_quote!(#[emit_route(timeout = 1, method = "GET")]),
+            r#"emit_route (method = "GET" , timeout = 1)"#,
+        ),
+    ] {
+        let attr: Attribute = attr;
+        let tokens = if attr.path.is_ident("emit_leading") {
+            let value = EmitLeading::try_from(std::slice::from_ref(&attr)).unwrap();
+            round_trip::<EmitLeading>(attr, |value| parse_quote!(#value));
+            value.to_meta_list(true).to_token_stream()
+        } else {
+            let value = EmitRoute::try_from(std::slice::from_ref(&attr)).unwrap();
+            round_trip::<EmitRoute>(attr, |value| parse_quote!(#value));
+            value.to_meta_list(true).to_token_stream()
+        };
+        assert_eq!(tokens.to_string(), emitted);
+    }
+}
+
+#[test]
+fn test_emit_lit() {
+    use macro_input::ToLit;
+
+    for level in [Level::Low, Level::High] {
+        let value = level.to_value();
+        assert_eq!(<Level as FromLit>::from_value(value).unwrap(), level);
+    }
+    let value = Level::High.to_value();
+    assert_eq!(value.to_token_stream().to_string(), r#""HIGH""#);
+
+    // non-finite floats are emitted as constants
+    for (float, tokens) in [
+        (f64::INFINITY, "f64 :: INFINITY"),
+        (f64::NEG_INFINITY, "f64 :: NEG_INFINITY"),
+        (-0.5, "- 0.5"),
+    ] {
+        let value = float.to_value();
+        assert_eq!(value.to_token_stream().to_string(), tokens);
+        assert_eq!(<f64 as FromLit>::from_value(value).unwrap(), float);
+    }
+    let value = f32::NAN.to_value();
+    assert_eq!(value.to_token_stream().to_string(), "f32 :: NAN");
+    assert!(<f32 as FromLit>::from_value(value).unwrap().is_nan());
+    assert!(<f32 as FromLit>::from_value(f64::NAN.to_value()).is_err());
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct HelpCommon {
+    /// print more details
+    pub verbose: bool,
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(parse)]
+pub struct HelpInput {
+    /// the path of the route
+    #[macro_input(positional)]
+    pub path: String,
+    /// request timeout
+    ///
+    /// in seconds
+    #[macro_input(default_value = 3, example = "timeout = 5", example = "timeout = 10")]
+    pub timeout: i32,
+    pub tags: Vec<syn::Path>,
+    pub bounds: Option<syn::punctuated::Punctuated<syn::WherePredicate, syn::token::Comma>>,
+    /// only for tests
+    #[macro_input(hidden)]
+    pub secret: Option<()>,
+    #[macro_input(flatten)]
+    pub common: HelpCommon,
+}
+
+#[test]
+fn test_help() {
+    assert_eq!(
+        HelpInput::FIELD_DEFS.help(),
+        "`path = <String>` (required, position 0): the path of the route\n\
+         `timeout = <i32>` (default 3): request timeout in seconds\n    \
+         eg `timeout = 5`\n    \
+         eg `timeout = 10`\n\
+         `tags = <syn::Path>`\n\
+         `bounds = <syn::punctuated::Punctuated<syn::WherePredicate, syn::token::Comma>>`\n\
+         `verbose`: print more details"
+    );
+
+    // hidden fields are still parsed
+    let res: HelpInput = syn::parse_str(r#""/", secret"#).unwrap();
+    assert_eq!(res.secret, Some(()));
+
+    // unknown keys are reported with the keys from the help text
+    let err = syn::parse_str::<HelpInput>(r#""/", tgas = "a""#).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unrecognized attribute: help_input::tgas, expected one of: path, timeout, tags, bounds, verbose"
+    );
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(allow_unknown, parse)]
+pub struct LenientInput {
+    pub size: Option<u32>,
+}
+
+#[test]
+fn test_unknown_keys() {
+    let attrs: Vec<Attribute> = vec![
+        parse_quote!(#[input(requird = 3, flag)]),
+        parse_quote!(#[input(required = 3, optinal = 1)]),
+        parse_quote!(#[other(unrelated = 1)]),
+    ];
+    let err = Input::try_from(attrs.as_slice()).unwrap_err();
+    let messages = err.into_iter().map(|e| e.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "unrecognized attribute: input::requird, expected one of: flag, optional, with_default, required",
+            "unrecognized attribute: input::optinal, expected one of: flag, optional, with_default, required",
+        ]
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[lenient_input(size = 3, colour = "red")])];
+    let res = LenientInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(res, LenientInput { size: Some(3) });
+    let res: LenientInput = syn::parse_str(r#"colour = "red""#).unwrap();
+    assert_eq!(res, LenientInput { size: None });
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[input(required = 3, flag)])];
+    assert!(Input::FIELD_DEFS.check_unknown(&attrs).is_ok());
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct PolicyAuth {
+    pub user: Option<String>,
+    pub realm: Option<String>,
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(parse)]
+pub struct PolicyInput {
+    pub strict: Option<u32>,
+    #[macro_input(duplicates = "first")]
+    pub first: Option<u32>,
+    #[macro_input(duplicates = "last")]
+    pub last: Option<u32>,
+    #[macro_input(duplicates = "merge")]
+    pub auth: Option<PolicyAuth>,
+    pub tags: Vec<String>,
+}
+
+#[test]
+fn test_duplicates() {
+    let attrs: Vec<Attribute> = vec![
+        parse_quote!(#[policy_input(first = 1, last = 1, auth(user = "x"), tags = "a")]),
+        parse_quote!(#[policy_input(first = 2, last = 2, auth(realm = "y"), tags = "b")]),
+    ];
+    let res = PolicyInput::try_from(attrs.as_slice()).unwrap();
+    assert_eq!(
+        res,
+        PolicyInput {
+            strict: None,
+            first: Some(1),
+            last: Some(2),
+            auth: Some(PolicyAuth {
+                user: Some("x".to_string()),
+                realm: Some("y".to_string()),
+            }),
+            tags: vec!["a".to_string(), "b".to_string()],
+        }
+    );
+
+    // both occurrences are reported
+    let attrs: Vec<Attribute> = vec![
+        parse_quote!(#[policy_input(strict = 1)]),
+        parse_quote!(#[policy_input(strict = 2)]),
+    ];
+    let err = PolicyInput::try_from(attrs.as_slice()).unwrap_err();
+    let messages = err.into_iter().map(|e| e.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec!["duplicate strict attribute", "strict is first given here"]
+    );
+
+    let res: Result<PolicyInput, _> = syn::parse_str("strict = 1, strict = 1");
+    assert!(res.is_err());
+
+    // merged lists still can't repeat their keys
+    let attrs: Vec<Attribute> = vec![
+        parse_quote!(#[policy_input(auth(user = "x"))]),
+        parse_quote!(#[policy_input(auth(user = "y"))]),
+    ];
+    assert!(PolicyInput::try_from(attrs.as_slice()).is_err());
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub struct ReportInner {
+    pub depth: u8,
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(parse)]
+pub struct ReportInput {
+    pub name: String,
+    #[macro_input(default_value = 3)]
+    pub retries: u32,
+    pub label: Option<String>,
+    pub tags: Vec<String>,
+    pub inner: Option<ReportInner>,
+}
+
+#[test]
+fn test_all_errors() {
+    let attr: Attribute = parse_quote!(#[report_input(
+        retries = "3",
+        label = 1,
+        tags = 2,
+        inner(depth = -1),
+        colour = "red"
+    )]);
+    let attrs = [attr];
+    let err = ReportInput::try_from(&attrs as &[Attribute]).unwrap_err();
+    assert_eq!(err.into_iter().count(), 6);
+
+    // nothing can be filled in for the missing name
+    let partial = ReportInput::try_from_partial(&attrs);
+    assert!(partial.value.is_none());
+    assert!(partial.error.is_some());
+
+    // the others fall back to their defaults
+    let attr: Attribute = parse_quote!(#[report_input(name = "x", retries = "3", label = 1)]);
+    let partial = ReportInput::try_from_partial(&[attr]);
+    assert_eq!(
+        partial.value,
+        Some(ReportInput {
+            name: "x".to_string(),
+            retries: 3,
+            label: None,
+            tags: Vec::new(),
+            inner: None,
+        })
+    );
+    assert_eq!(partial.error.unwrap().into_iter().count(), 2);
+
+    let res: Result<ReportInput, _> = syn::parse_str("name = 1, retries = -1");
+    assert_eq!(res.unwrap_err().into_iter().count(), 2);
+
+    let attr: Attribute = parse_quote!(#[report_input(retries = "3", colour = "red")]);
+    let err = ReportInput::FIELD_DEFS.check(&[attr]).unwrap_err();
+    assert_eq!(err.into_iter().count(), 3);
+}
+
+#[test]
+fn test_missing_span() {
+    let item: syn::ItemStruct = syn::parse_str("\n  struct Report;").unwrap();
+    let span = item.ident.span();
+
+    // without an attribute the error points at the span
+    let err = ReportInput::try_from_spanned(&[], span).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "attribute for required field not found: report_input::name"
+    );
+    assert_eq!(err.span().start(), span.start());
+
+    // the attribute is there but lacks the key so the error points at the attribute
+    let attrs = parse_attrs("#[other]\n#[report_input(retries = 3)]");
+    let partial = ReportInput::try_from_partial_spanned(&attrs, span);
+    assert!(partial.value.is_none());
+    let err = partial.error.unwrap();
+    assert_eq!(
+        err.to_string(),
+        "attribute for required field not found: report_input::name"
+    );
+    assert_eq!((err.span().start().line, err.span().start().column), (2, 0));
+
+    let attr: Attribute = parse_quote!(#[report_input(name = "x")]);
+    assert!(ReportInput::try_from_spanned(&[attr], span).is_ok());
+
+    // arguments without a key point at the span too
+    let err = ReportInput::from_nested_spanned(&[], span).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "field required by report_input not found: name"
+    );
+    assert_eq!(err.span().start(), span.start());
+    let partial = ReportInput::from_meta_partial_spanned(None, span);
+    assert_eq!(partial.error.unwrap().span().start(), span.start());
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(validate = "check_route", parse)]
+pub struct RouteInput {
+    #[macro_input(validate = "starts_with_slash")]
+    pub path: String,
+    #[macro_input(range = "1..=10")]
+    pub timeout: Option<u32>,
+    #[macro_input(one_of = ["GET", "POST"])]
+    pub method: Vec<String>,
+    #[macro_input(non_empty, ident)]
+    pub name: Option<String>,
+}
+
+fn starts_with_slash(path: &str, span: proc_macro2::Span) -> syn::Result<()> {
+    if path.starts_with('/') {
+        Ok(())
+    } else {
+        Err(Error::new(span, "path must start with /"))
+    }
+}
+
+fn check_route(route: &RouteInput) -> syn::Result<()> {
+    if route.path == "/" && route.timeout.is_some() {
+        Err(Error::new(
+            proc_macro2::Span::call_site(),
+            "the root can't have a timeout",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_validate() {
+    let res: RouteInput =
+        syn::parse_str(r#"path = "/a", timeout = 5, method = "GET", method = "POST", name = "a""#)
+            .unwrap();
+    assert_eq!(
+        res,
+        RouteInput {
+            path: "/a".to_string(),
+            timeout: Some(5),
+            method: vec!["GET".to_string(), "POST".to_string()],
+            name: Some("a".to_string()),
+        }
+    );
+
+    let messages = |args: &str| {
+        syn::parse_str::<RouteInput>(args)
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        messages(r#"path = "a", timeout = 0, method = "PUT", name = "1""#),
+        vec![
+            "path must start with /",
+            "expected a value in 1..=10, got 0",
+            r#"expected one of: "GET", "POST""#,
+            r#"expected an identifier, got "1""#,
+        ]
+    );
+    assert_eq!(
+        messages(r#"path = "/", name = """#),
+        vec![
+            "expected a non-empty string",
+            r#"expected an identifier, got """#
+        ]
+    );
+    assert_eq!(
+        messages(r#"path = "/", timeout = 3"#),
+        vec!["the root can't have a timeout"]
+    );
+
+    // a validated field that's given twice is reported once
+    assert_eq!(
+        messages(r#"path = "/a", path = "b""#),
+        vec!["duplicate path attribute", "path is first given here"]
+    );
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(
+    exactly_one_of("sync", "async"),
+    at_least_one_of("key", "cert", "method"),
+    parse
+)]
+pub struct ConstraintsInput {
+    pub sync: Flag,
+    #[macro_input(rename = "async", conflicts_with = "sync")]
+    pub is_async: Flag,
+    pub key: Option<String>,
+    #[macro_input(requires = "key")]
+    pub cert: Option<String>,
+    pub method: Option<String>,
+    #[macro_input(required_if(method = "POST"))]
+    pub body: Option<String>,
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+#[macro_input(parse)]
+pub struct DefaultedConstraintsInput {
+    #[macro_input(default_value = "POST")]
+    pub method: String,
+    #[macro_input(required_if(method = "POST"))]
+    pub body: Option<String>,
+}
+
+#[test]
+fn test_constraints() {
+    let res: ConstraintsInput =
+        syn::parse_str(r#"sync, key = "a", cert = "b", method = "POST", body = "c""#).unwrap();
+    assert!(res.sync.is_set());
+    assert_eq!(res.body, Some("c".to_string()));
+
+    // the message and the column of every error
+    let errors = |args: &str| {
+        syn::parse_str::<ConstraintsInput>(args)
+            .unwrap_err()
+            .into_iter()
+            .map(|e| (e.to_string(), e.span().start().column))
+            .collect::<Vec<_>>()
+    };
+    let error = |message: &str, column: usize| (message.to_string(), column);
+    assert_eq!(
+        errors(r#"sync, async, key = "a""#),
+        vec![
+            error("async can't be combined with sync", 6),
+            error("async can't be combined with sync", 0),
+            error("only one of sync, async may be specified", 0),
+            error("only one of sync, async may be specified", 6),
+        ]
+    );
+    assert_eq!(
+        errors(r#"sync, cert = "b""#),
+        vec![error("cert requires key", 6)]
+    );
+    assert_eq!(
+        errors(r#"sync, method = "POST""#),
+        vec![error(r#"body is required if method = "POST""#, 6)]
+    );
+    assert_eq!(
+        errors(r#"method = "GET""#),
+        vec![error("expected exactly one of: sync, async", 0)]
+    );
+    assert_eq!(
+        errors("sync"),
+        vec![error("expected at least one of: key, cert, method", 0)]
+    );
+
+    let attr: Attribute = parse_quote!(#[constraints_input(async, method = "GET")]);
+    assert!(ConstraintsInput::FIELD_DEFS
+        .check_constraints(&[attr])
+        .is_ok());
+
+    // conditions on fields that aren't given are checked against their default value
+    let attrs = parse_attrs("#[other]\n#[defaulted_constraints_input()]");
+    let err = DefaultedConstraintsInput::try_from(attrs.as_slice()).unwrap_err();
+    assert_eq!(err.to_string(), r#"body is required if method = "POST""#);
+    assert_eq!((err.span().start().line, err.span().start().column), (2, 0));
+    let res: DefaultedConstraintsInput = syn::parse_str(r#"method = "GET""#).unwrap();
+    assert_eq!(res.body, None);
+    let res: DefaultedConstraintsInput = syn::parse_str(r#"body = "a""#).unwrap();
+    assert_eq!(res.method, "POST");
+}
+
+#[derive(MacroInput, PartialEq, Debug)]
+pub enum KeywordInput {
+    #[macro_input(rename = "struct")]
+    Struct,
+    #[macro_input(alias = "fn")]
+    Function(String),
+}
+
+#[test]
+fn test_keyword_names() {
+    let attr: Attribute = parse_quote!(#[keyword_input(struct)]);
+    let res = KeywordInput::try_from(&[attr] as &[Attribute]).unwrap();
+    assert_eq!(res, KeywordInput::Struct);
+
+    let attr: Attribute = parse_quote!(#[keyword_input(fn = "main")]);
+    let res = KeywordInput::try_from(&[attr] as &[Attribute]).unwrap();
+    assert_eq!(res, KeywordInput::Function("main".to_owned()));
+}
@@ -1,6 +1,10 @@
-use macro_input::{Def, DefaultValue, FromMeta};
+use macro_input::{Def, DefaultValue, Defs, Duplicates, FromMeta, Meta, MetaList, ToMeta};
+use quote::ToTokens;
 use std::fmt::Debug;
-use syn::{parse_quote, Attribute};
+use syn::{
+    parse_quote, punctuated::Punctuated, token::Comma, Attribute, Expr, Ident, LitStr, Path, Type,
+    WherePredicate,
+};
 
 #[test]
 fn test_string() {
@@ -41,6 +45,322 @@ fn test_default_string() {
     );
 }
 
+#[test]
+fn test_integers() {
+    const FIELD: Def = Def::new("foo", "bar", true, DefaultValue::U64(None));
+
+    test_field::<u8>(parse_quote!(#[foo(bar = 5)]), &FIELD, 5);
+    test_field::<u8>(parse_quote!(#[foo(bar = b'a')]), &FIELD, b'a');
+    test_field::<u16>(parse_quote!(#[foo(bar = 8080)]), &FIELD, 8080);
+    test_field::<u64>(parse_quote!(#[foo(bar = 1_000_000u64)]), &FIELD, 1_000_000);
+    test_field::<i128>(parse_quote!(#[foo(bar = 7)]), &FIELD, 7);
+    test_field::<usize>(parse_quote!(#[foo(bar = 3)]), &FIELD, 3);
+    test_field::<isize>(parse_quote!(#[foo(bar = 3)]), &FIELD, 3);
+}
+
+#[test]
+fn test_integer_out_of_range() {
+    const FIELD: Def = Def::new("foo", "bar", true, DefaultValue::I8(None));
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(bar = 300)])];
+    let err = FIELD.get_value::<i8>(&attrs).unwrap_err();
+    assert_eq!(err.to_string(), "expected i8 in range -128..=127");
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(bar = 3u32)])];
+    assert!(FIELD.get_value::<i8>(&attrs).is_err());
+}
+
+#[test]
+fn test_floats() {
+    const FIELD: Def = Def::new("foo", "bar", false, DefaultValue::F64(Some(0.5)));
+
+    test_field::<f64>(parse_quote!(#[foo(bar = 1.25)]), &FIELD, 1.25);
+    test_field::<f64>(parse_quote!(#[foo(bar = 2)]), &FIELD, 2.0);
+    test_field::<f64>(parse_quote!(#[foo(other = 2)]), &FIELD, 0.5);
+    test_field::<f32>(parse_quote!(#[foo(bar = 1.5f32)]), &FIELD, 1.5);
+}
+
+#[test]
+fn test_all_values() {
+    const FIELD: Def = Def::new("foo", "derive", false, DefaultValue::Str(None));
+
+    let attrs: Vec<Attribute> = vec![
+        parse_quote!(#[foo(derive = "A", other = 1, derive = "B")]),
+        parse_quote!(#[foo(derive("C", "D"))]),
+    ];
+    assert_eq!(
+        FIELD.get_all_values::<String>(&attrs).unwrap(),
+        vec!["A", "B", "C", "D"]
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(other = 1)])];
+    assert!(FIELD.get_all_values::<String>(&attrs).unwrap().is_empty());
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(derive("A", 1))])];
+    let err = FIELD.get_all_values::<String>(&attrs).unwrap_err();
+    assert_eq!(err.to_string(), "expected string");
+}
+
+#[test]
+fn test_bool() {
+    const FIELD: Def = Def::new("foo", "bar", false, DefaultValue::Bool(None));
+
+    test_field::<bool>(parse_quote!(#[foo(bar)]), &FIELD, true);
+    test_field::<bool>(parse_quote!(#[foo(bar = true)]), &FIELD, true);
+    test_field::<bool>(parse_quote!(#[foo(bar = false)]), &FIELD, false);
+    test_field::<bool>(parse_quote!(#[foo(other)]), &FIELD, false);
+    test_field::<Option<bool>>(parse_quote!(#[foo(bar)]), &FIELD, Some(true));
+    test_field::<Option<bool>>(parse_quote!(#[foo(other)]), &FIELD, None);
+}
+
+#[test]
+fn test_syntax_trees() {
+    const FIELD: Def = Def::new("foo", "bar", true, DefaultValue::Str(None));
+
+    test_field::<Path>(
+        parse_quote!(#[foo(bar = "my::module")]),
+        &FIELD,
+        parse_quote!(my::module),
+    );
+    test_field::<Ident>(parse_quote!(#[foo(bar = "baz")]), &FIELD, parse_quote!(baz));
+    test_field::<Type>(
+        parse_quote!(#[foo(bar = "Vec<u8>")]),
+        &FIELD,
+        parse_quote!(Vec<u8>),
+    );
+    test_field::<Expr>(
+        parse_quote!(#[foo(bar = "Vec::new()")]),
+        &FIELD,
+        parse_quote!(Vec::new()),
+    );
+    test_field::<WherePredicate>(
+        parse_quote!(#[foo(bar = "T: Clone")]),
+        &FIELD,
+        parse_quote!(T: Clone),
+    );
+    test_field::<Punctuated<WherePredicate, Comma>>(
+        parse_quote!(#[foo(bar = "T: Clone, U: Copy")]),
+        &FIELD,
+        parse_quote!(T: Clone, U: Copy),
+    );
+    test_field::<LitStr>(
+        parse_quote!(#[foo(bar = "baz")]),
+        &FIELD,
+        parse_quote!("baz"),
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(bar = "Vec<")])];
+    assert!(FIELD.get_value::<Type>(&attrs).is_err());
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(bar = 1)])];
+    let err = FIELD.get_value::<Path>(&attrs).unwrap_err();
+    assert_eq!(err.to_string(), "expected string containing a path");
+}
+
+#[test]
+fn test_values() {
+    const FIELD: Def = Def::new("foo", "bar", true, DefaultValue::Any(None));
+
+    test_field::<i32>(parse_quote!(#[foo(bar = -4)]), &FIELD, -4);
+    test_field::<f64>(parse_quote!(#[foo(bar = -0.5)]), &FIELD, -0.5);
+    test_field::<Path>(parse_quote!(#[foo(bar = Json)]), &FIELD, parse_quote!(Json));
+    test_field::<Expr>(
+        parse_quote!(#[foo(bar = some::CONST)]),
+        &FIELD,
+        parse_quote!(some::CONST),
+    );
+    test_field::<Expr>(
+        parse_quote!(#[foo(bar = (1, 2))]),
+        &FIELD,
+        parse_quote!((1, 2)),
+    );
+    test_field::<Type>(
+        parse_quote!(#[foo(bar = Vec<u8>)]),
+        &FIELD,
+        parse_quote!(Vec<u8>),
+    );
+    test_field::<String>(parse_quote!(#[foo(bar = "baz")]), &FIELD, "baz".to_string());
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(bar = [1, -2, 3])])];
+    assert_eq!(FIELD.get_all_values::<i32>(&attrs).unwrap(), vec![1, -2, 3]);
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(bar(Clone, some::Trait))])];
+    assert_eq!(
+        FIELD.get_all_values::<Path>(&attrs).unwrap(),
+        vec![parse_quote!(Clone), parse_quote!(some::Trait)] as Vec<Path>
+    );
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(bar = -1)])];
+    let err = FIELD.get_value::<u32>(&attrs).unwrap_err();
+    assert_eq!(err.to_string(), "expected u32 in range 0..=4294967295");
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(bar = Json)])];
+    let err = FIELD.get_value::<String>(&attrs).unwrap_err();
+    assert_eq!(err.to_string(), "expected literal");
+
+    const DEFAULT_FIELD: Def = Def::new(
+        "foo",
+        "bar",
+        false,
+        DefaultValue::AnyStr(Some("some::CONST")),
+    );
+    const INVALID_FIELD: Def = Def::new("foo", "bar", false, DefaultValue::AnyStr(Some("(1")));
+    assert_eq!(
+        DEFAULT_FIELD.get_value::<Path>(&[]).unwrap(),
+        parse_quote!(some::CONST)
+    );
+    let err = INVALID_FIELD.get_value::<Option<i32>>(&[]).unwrap_err();
+    assert_eq!(err.to_string(), "invalid default value: (1");
+
+    const VALUE_FIELD: Def = Def::new(
+        "foo",
+        "bar",
+        false,
+        DefaultValue::AnyValue(Some(|| parse_quote!(some::CONST))),
+    );
+    assert_eq!(
+        VALUE_FIELD.get_value::<Path>(&[]).unwrap(),
+        parse_quote!(some::CONST)
+    );
+    let tokens = VALUE_FIELD.default.to_token_stream().to_string();
+    assert!(tokens.starts_with(":: macro_input :: DefaultValue :: AnyValue"));
+    assert!(tokens.contains("parse_quote ! (some :: CONST)"));
+}
+
+#[test]
+fn test_positional() {
+    const PATH_FIELD: Def = Def::new("foo", "path", true, DefaultValue::Str(None)).with_position(0);
+    const LEN_FIELD: Def =
+        Def::new("foo", "len", false, DefaultValue::U32(Some(1))).with_position(1);
+
+    test_field::<String>(parse_quote!(#[foo("a", 2)]), &PATH_FIELD, "a".to_string());
+    test_field::<u32>(parse_quote!(#[foo("a", 2)]), &LEN_FIELD, 2);
+    test_field::<u32>(parse_quote!(#[foo("a")]), &LEN_FIELD, 1);
+    test_field::<u32>(parse_quote!(#[foo("a", len = 3)]), &LEN_FIELD, 3);
+    // values after a key aren't positional
+    test_field::<u32>(parse_quote!(#[foo(path = "a", 2)]), &LEN_FIELD, 1);
+}
+
+#[test]
+fn test_aliases() {
+    const FIELD: Def = Def::new("foo", "bar", false, DefaultValue::U32(Some(1)))
+        .with_aliases(&["baz"])
+        .with_path_aliases(&["f"]);
+
+    test_field::<u32>(parse_quote!(#[foo(bar = 2)]), &FIELD, 2);
+    test_field::<u32>(parse_quote!(#[foo(baz = 3)]), &FIELD, 3);
+    test_field::<u32>(parse_quote!(#[f(baz = 4)]), &FIELD, 4);
+    test_field::<u32>(parse_quote!(#[other(baz = 4)]), &FIELD, 1);
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(bar = 2)]), parse_quote!(#[f(baz = 3)])];
+    assert!(FIELD.get_value::<u32>(&attrs).is_err());
+
+    // repeating the same name is fine for repeated values
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(baz = 2, baz = 3)])];
+    assert_eq!(FIELD.get_all_values::<u32>(&attrs).unwrap(), vec![2, 3]);
+}
+
+#[test]
+fn test_duplicates() {
+    const FIELD: Def = Def::new("foo", "bar", false, DefaultValue::U32(None));
+    const FIRST_FIELD: Def = FIELD.with_duplicates(Duplicates::First);
+    const MERGE_FIELD: Def = FIELD.with_duplicates(Duplicates::Merge);
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(bar = 1, bar = 2)])];
+    let err = FIELD.get_value::<u32>(&attrs).unwrap_err();
+    assert_eq!(err.to_string(), "duplicate bar attribute");
+    assert_eq!(FIRST_FIELD.get_value::<u32>(&attrs).unwrap(), 1);
+    assert_eq!(
+        MERGE_FIELD
+            .get_value::<MetaList>(&attrs)
+            .unwrap()
+            .nested
+            .len(),
+        2
+    );
+    assert_eq!(FIELD.get_all_values::<u32>(&attrs).unwrap(), vec![1, 2]);
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(bar = 1)])];
+    assert!(matches!(
+        MERGE_FIELD.get_meta(&attrs).unwrap(),
+        Some(Meta::NameValue(_))
+    ));
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(bar, bar)])];
+    assert!(MERGE_FIELD.get_meta(&attrs).is_err());
+}
+
+#[test]
+fn test_flattened_constraints() {
+    const DEBUG_FIELD: Def = Def::new("foo", "debug", false, DefaultValue::Flag);
+    const COMMON_FIELDS: &[&Def] = &[&DEBUG_FIELD];
+    const COMMON_FIELD_DEFS: Defs = Defs::new(COMMON_FIELDS);
+
+    const COMMON_FIELD: Def = Def::new("foo", "common", false, DefaultValue::Any(None))
+        .with_flattened(&COMMON_FIELD_DEFS)
+        .with_conflicts_with(&["quiet"]);
+    const QUIET_FIELD: Def = Def::new("foo", "quiet", false, DefaultValue::Flag);
+    const FIELDS: &[&Def] = &[&COMMON_FIELD, &QUIET_FIELD];
+    const FIELD_DEFS: Defs = Defs::new(FIELDS).with_at_least_one_of(&[&["common", "quiet"]]);
+
+    // a flattened field is only given if one of its keys is
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(quiet)])];
+    assert!(FIELD_DEFS.check_constraints(&attrs).is_ok());
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(debug)])];
+    assert!(FIELD_DEFS.check_constraints(&attrs).is_ok());
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo()])];
+    let err = FIELD_DEFS.check_constraints(&attrs).unwrap_err();
+    assert_eq!(err.to_string(), "expected at least one of: common, quiet");
+
+    let attrs: Vec<Attribute> = vec![parse_quote!(#[foo(debug, quiet)])];
+    let err = FIELD_DEFS.check_constraints(&attrs).unwrap_err();
+    assert_eq!(err.to_string(), "common can't be combined with quiet");
+
+    let list: MetaList = parse_quote!(foo(quiet));
+    assert!(FIELD_DEFS.check_constraints_nested(&list).is_ok());
+    let list: MetaList = parse_quote!(foo(debug, quiet));
+    assert!(FIELD_DEFS.check_constraints_nested(&list).is_err());
+}
+
+#[test]
+fn test_to_nested() {
+    const FIELD: Def = Def::new("foo", "bar", false, DefaultValue::Any(None));
+    const DEFAULT_FIELD: Def = Def::new("foo", "bar", false, DefaultValue::I32(Some(-3)));
+    const BOOL_FIELD: Def = Def::new("foo", "bar", false, DefaultValue::Bool(None));
+
+    test_round_trip(&FIELD, "baz".to_string());
+    test_round_trip(&FIELD, b"\x00baz".to_vec());
+    test_round_trip(&FIELD, 'x');
+    test_round_trip(&FIELD, 255u8);
+    test_round_trip(&FIELD, i64::MIN);
+    test_round_trip(&FIELD, u128::MAX);
+    test_round_trip(&FIELD, -0.1f32);
+    test_round_trip(&FIELD, 1e300f64);
+    test_round_trip(&FIELD, true);
+    test_round_trip(&FIELD, false);
+    test_round_trip(&FIELD, Some(()));
+    test_round_trip(&FIELD, None::<()>);
+    test_round_trip(&FIELD, Some(3u32));
+    test_round_trip(&FIELD, None::<u32>);
+    test_round_trip::<Path>(&FIELD, parse_quote!(::my::module));
+    test_round_trip::<Ident>(&FIELD, parse_quote!(r#type));
+    test_round_trip::<Type>(&FIELD, parse_quote!(HashMap<String, Vec<u8>>));
+    test_round_trip::<Expr>(&FIELD, parse_quote!(f("a, b", -1)));
+    test_round_trip::<WherePredicate>(&FIELD, parse_quote!(T: Clone));
+    test_round_trip::<Punctuated<WherePredicate, Comma>>(&FIELD, parse_quote!(T: Clone, U: Copy));
+    test_round_trip::<LitStr>(&FIELD, parse_quote!("baz"));
+
+    // default values can be omitted
+    assert!(DEFAULT_FIELD.to_nested(&-3, true).is_none());
+    assert!(DEFAULT_FIELD.to_nested(&-3, false).is_some());
+    assert!(DEFAULT_FIELD.to_nested(&3, true).is_some());
+    assert!(BOOL_FIELD.to_nested(&false, true).is_none());
+    assert!(BOOL_FIELD.to_nested(&true, true).is_some());
+}
+
+/// emit the value as an attribute and parse it again
+fn test_round_trip<T: FromMeta + ToMeta + PartialEq + Debug>(field: &Def, value: T) {
+    let nested = field.to_nested(&value, false);
+    test_field::<T>(parse_quote!(#[foo(#nested)]), field, value);
+}
+
 fn test_field<T: FromMeta + PartialEq + Debug>(attr: Attribute, field: &Def, value: T) {
     let attrs = vec![attr];
     assert_eq!(field.get_value::<T>(&attrs).unwrap(), value);